@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Clock consumers and simple providers
+//!
+//! Reference: https://github.com/devicetree-org/dt-schema/blob/main/dtschema/schemas/clock/clock.yaml
+
+use crate::node::{FdtNode, MAX_DEPTH};
+use crate::parsing::{BigEndianU32, BigEndianU64};
+use crate::phandle::PhandleArgs;
+
+/// A clock input of a node: the provider and the specifier cells
+#[derive(Debug, Clone, Copy)]
+pub struct Clock<'b, 'a> {
+    /// Name from `clock-names`, if given
+    pub name: Option<&'a str>,
+    pub(crate) spec: PhandleArgs<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Clock<'b, 'a> {
+    /// Clock provider node
+    pub fn provider(&self) -> FdtNode<'b, 'a> {
+        self.spec.node
+    }
+
+    /// Specifier cells, `#clock-cells` long
+    pub fn specifier(&self) -> &[u32] {
+        self.spec.args()
+    }
+
+    /// Index of the clock in the `clocks` property
+    pub fn index(&self) -> usize {
+        self.spec.index
+    }
+
+    /// Output name of the provider from `clock-output-names`
+    pub fn output_name(&self) -> Option<&'a str> {
+        let index = self.specifier().first().copied().unwrap_or(0) as usize;
+        self.provider().string_at("clock-output-names", index)
+    }
+
+    /// Frequency in Hz, for `fixed-clock` providers and `fixed-factor-clock`
+    /// chains ending in one
+    pub fn rate(&self) -> Option<u64> {
+        provider_rate(self.provider(), 0)
+    }
+}
+
+/// One entry of `assigned-clocks` with the rate and parent to set up
+#[derive(Debug, Clone, Copy)]
+pub struct AssignedClock<'b, 'a> {
+    /// Clock to configure
+    pub clock: Clock<'b, 'a>,
+    /// Rate from `assigned-clock-rates`, `None` when absent or 0
+    pub rate: Option<u64>,
+    /// Parent from `assigned-clock-parents`, `None` when absent or empty
+    pub parent: Option<Clock<'b, 'a>>,
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// Clock inputs from `clocks`, named by `clock-names`
+    pub fn clocks(self) -> impl Iterator<Item = Clock<'b, 'a>> + 'b {
+        self.phandle_args("clocks", "#clock-cells")
+            .map(move |spec| Clock { name: self.string_at("clock-names", spec.index), spec })
+    }
+
+    /// Clock input listed as `name` in `clock-names`
+    pub fn clock(self, name: &str) -> Option<Clock<'b, 'a>> {
+        let index = self.string_index("clock-names", name)?;
+        self.clocks().find(|clock| clock.index() == index)
+    }
+
+    /// `assigned-clocks` with their `assigned-clock-rates` and
+    /// `assigned-clock-parents`
+    pub fn assigned_clocks(self) -> impl Iterator<Item = AssignedClock<'b, 'a>> + 'b {
+        self.phandle_args("assigned-clocks", "#clock-cells").map(move |spec| {
+            let index = spec.index;
+            let parent = self.phandle_args("assigned-clock-parents", "#clock-cells")
+                .find(|parent| parent.index == index)
+                .map(|spec| Clock { name: None, spec });
+
+            AssignedClock { clock: Clock { name: None, spec }, rate: self.assigned_rate(index), parent }
+        })
+    }
+
+    fn assigned_rate(self, index: usize) -> Option<u64> {
+        let rate = match self.property("assigned-clock-rates-u64") {
+            Some(rates) => BigEndianU64::from_bytes(rates.value.get(index * 8..)?)?.get(),
+            None => {
+                let rates = self.property("assigned-clock-rates")?;
+                BigEndianU32::from_bytes(rates.value.get(index * 4..)?)?.get() as u64
+            }
+        };
+
+        (rate != 0).then_some(rate)
+    }
+}
+
+/// Rate of a simple clock provider, following `fixed-factor-clock` parents
+fn provider_rate(node: FdtNode<'_, '_>, depth: usize) -> Option<u64> {
+    if depth >= MAX_DEPTH {
+        return None;
+    }
+
+    let compatible = node.compatible()?;
+    if compatible.all().any(|c| c == "fixed-clock") {
+        return node.property("clock-frequency")?.as_usize().map(|f| f as u64);
+    }
+    if compatible.all().any(|c| c == "fixed-factor-clock") {
+        let mult = node.property("clock-mult")?.as_usize()? as u64;
+        let div = node.property("clock-div")?.as_usize()? as u64;
+        let parent = node.clocks().next()?;
+        return provider_rate(parent.provider(), depth + 1)?.checked_mul(mult)?.checked_div(div);
+    }
+
+    None
+}
@@ -0,0 +1,299 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Ethernet controllers, MDIO buses, PHYs and switch ports
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/net/ethernet-controller.yaml,
+//! https://www.kernel.org/doc/Documentation/devicetree/bindings/net/ethernet-phy.yaml
+//! and https://www.kernel.org/doc/Documentation/devicetree/bindings/net/dsa/dsa.yaml
+
+use crate::bindings::Gpio;
+use crate::node::FdtNode;
+use crate::parsing::BigEndianU32;
+
+/// Interface between a MAC and its PHY, from `phy-mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhyMode {
+    /// `internal`
+    Internal,
+    /// `mii`
+    Mii,
+    /// `gmii`
+    Gmii,
+    /// `sgmii`
+    Sgmii,
+    /// `tbi`
+    Tbi,
+    /// `rev-mii`
+    RevMii,
+    /// `rmii`
+    Rmii,
+    /// `rev-rmii`
+    RevRmii,
+    /// `rgmii`
+    Rgmii,
+    /// `rgmii-id`: the PHY adds both RX and TX delays
+    RgmiiId,
+    /// `rgmii-rxid`: the PHY adds the RX delay
+    RgmiiRxid,
+    /// `rgmii-txid`: the PHY adds the TX delay
+    RgmiiTxid,
+    /// `rtbi`
+    Rtbi,
+    /// `smii`
+    Smii,
+    /// `xgmii`
+    Xgmii,
+    /// `xlgmii`
+    Xlgmii,
+    /// `moca`
+    Moca,
+    /// `psgmii`
+    Psgmii,
+    /// `qsgmii`
+    Qsgmii,
+    /// `qusgmii`
+    Qusgmii,
+    /// `trgmii`
+    Trgmii,
+    /// `100base-x`
+    Base100X,
+    /// `1000base-x`
+    Base1000X,
+    /// `1000base-kx`
+    Base1000Kx,
+    /// `2500base-x`
+    Base2500X,
+    /// `5gbase-r`
+    Base5gR,
+    /// `rxaui`
+    Rxaui,
+    /// `xaui`
+    Xaui,
+    /// `10gbase-r`
+    Base10gR,
+    /// `10gbase-kr`
+    Base10gKr,
+    /// `10g-qxgmii`
+    Qxgmii10g,
+    /// `25gbase-r`
+    Base25gR,
+    /// `usxgmii`
+    Usxgmii,
+    /// `50gbase-r`
+    Base50gR,
+    /// `100gbase-p`
+    Base100gP,
+}
+
+impl PhyMode {
+    /// Parses a `phy-mode` string
+    pub fn parse(mode: &str) -> Option<Self> {
+        Some(match mode {
+            "internal" => Self::Internal,
+            "mii" => Self::Mii,
+            "gmii" => Self::Gmii,
+            "sgmii" => Self::Sgmii,
+            "tbi" => Self::Tbi,
+            "rev-mii" => Self::RevMii,
+            "rmii" => Self::Rmii,
+            "rev-rmii" => Self::RevRmii,
+            "rgmii" => Self::Rgmii,
+            "rgmii-id" => Self::RgmiiId,
+            "rgmii-rxid" => Self::RgmiiRxid,
+            "rgmii-txid" => Self::RgmiiTxid,
+            "rtbi" => Self::Rtbi,
+            "smii" => Self::Smii,
+            "xgmii" => Self::Xgmii,
+            "xlgmii" => Self::Xlgmii,
+            "moca" => Self::Moca,
+            "psgmii" => Self::Psgmii,
+            "qsgmii" => Self::Qsgmii,
+            "qusgmii" => Self::Qusgmii,
+            "trgmii" => Self::Trgmii,
+            "100base-x" => Self::Base100X,
+            "1000base-x" => Self::Base1000X,
+            "1000base-kx" => Self::Base1000Kx,
+            "2500base-x" => Self::Base2500X,
+            "5gbase-r" => Self::Base5gR,
+            "rxaui" => Self::Rxaui,
+            "xaui" => Self::Xaui,
+            "10gbase-r" => Self::Base10gR,
+            "10gbase-kr" => Self::Base10gKr,
+            "10g-qxgmii" => Self::Qxgmii10g,
+            "25gbase-r" => Self::Base25gR,
+            "usxgmii" => Self::Usxgmii,
+            "50gbase-r" => Self::Base50gR,
+            "100gbase-p" => Self::Base100gP,
+            _ => return None,
+        })
+    }
+
+    /// Any of the RGMII variants
+    pub fn is_rgmii(self) -> bool {
+        matches!(self, Self::Rgmii | Self::RgmiiId | Self::RgmiiRxid | Self::RgmiiTxid)
+    }
+}
+
+/// A link without a PHY, from `fixed-link`
+#[derive(Debug, Clone, Copy)]
+pub struct FixedLink<'b, 'a> {
+    /// Speed in Mbit/s
+    pub speed: u32,
+    /// Full duplex
+    pub full_duplex: bool,
+    /// Symmetric pause
+    pub pause: bool,
+    /// Asymmetric pause
+    pub asym_pause: bool,
+    /// `link-gpios`: line reporting the link state
+    pub link_gpio: Option<Gpio<'b, 'a>>,
+}
+
+/// A device on an MDIO bus, usually a PHY
+#[derive(Debug, Clone, Copy)]
+pub struct Phy<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Phy<'b, 'a> {
+    /// Bus address from `reg`
+    pub fn address(&self) -> Option<u32> {
+        self.node.u32_property("reg")
+    }
+
+    /// PHY ID from an `ethernet-phy-idAAAA.BBBB` compatible, `0xAAAABBBB`
+    pub fn id(&self) -> Option<u32> {
+        self.node.compatible()?.all().find_map(|c| {
+            let (high, low) = c.strip_prefix("ethernet-phy-id")?.split_once('.')?;
+            let half = |s: &str| (s.len() == 4).then(|| u32::from_str_radix(s, 16).ok()).flatten();
+            Some(half(high)? << 16 | half(low)?)
+        })
+    }
+
+    /// Whether the PHY speaks Clause 45, `ethernet-phy-ieee802.3-c45`
+    pub fn is_c45(&self) -> bool {
+        self.node.compatible().is_some_and(|c| c.all().any(|c| c == "ethernet-phy-ieee802.3-c45"))
+    }
+}
+
+/// Represents an MDIO bus node with specific helper methods
+#[derive(Debug, Clone, Copy)]
+pub struct Mdio<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Mdio<'b, 'a> {
+    /// Wraps an MDIO bus node
+    pub fn new(node: FdtNode<'b, 'a>) -> Self {
+        Self { node }
+    }
+
+    /// The bus node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// Available devices on the bus, those with a `reg` address
+    pub fn phys(&self) -> impl Iterator<Item = Phy<'b, 'a>> + 'b {
+        self.node
+            .children()
+            .filter(|child| child.is_available() && child.property("reg").is_some())
+            .map(|node| Phy { node })
+    }
+
+    /// Device at bus address `address`
+    pub fn phy(&self, address: u32) -> Option<Phy<'b, 'a>> {
+        self.phys().find(|phy| phy.address() == Some(address))
+    }
+}
+
+/// A port of an Ethernet switch
+#[derive(Debug, Clone, Copy)]
+pub struct EthernetPort<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> EthernetPort<'b, 'a> {
+    /// Port number from `reg`
+    pub fn index(&self) -> Option<u32> {
+        self.node.u32_property("reg")
+    }
+
+    /// `label`, the name of the user port's network interface
+    pub fn label(&self) -> Option<&'a str> {
+        self.node.property("label")?.as_str()
+    }
+
+    /// Conduit Ethernet controller of a CPU port, from `ethernet`
+    pub fn ethernet(&self) -> Option<FdtNode<'b, 'a>> {
+        let phandle = self.node.u32_property("ethernet")?;
+        self.node.header.find_phandle(phandle)
+    }
+
+    /// Whether the port connects to a CPU Ethernet controller
+    pub fn is_cpu_port(&self) -> bool {
+        self.node.property("ethernet").is_some()
+    }
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// PHY from `phy-handle`, or the deprecated `phy` and `phy-device`
+    pub fn phy_handle(self) -> Option<FdtNode<'b, 'a>> {
+        let phandle = ["phy-handle", "phy", "phy-device"].iter().find_map(|name| self.u32_property(name))?;
+        self.header.find_phandle(phandle)
+    }
+
+    /// `phy-mode`, or the older `phy-connection-type`
+    pub fn phy_mode(self) -> Option<PhyMode> {
+        let mode = self.property("phy-mode").or_else(|| self.property("phy-connection-type"))?;
+        PhyMode::parse(mode.as_str()?)
+    }
+
+    /// `fixed-link` subnode, or the legacy
+    /// `<phy-id full-duplex speed pause asym-pause>` array
+    pub fn fixed_link(self) -> Option<FixedLink<'b, 'a>> {
+        if let Some(link) = self.children().find(|child| child.name == "fixed-link") {
+            return Some(FixedLink {
+                speed: link.u32_property("speed")?,
+                full_duplex: link.property("full-duplex").is_some(),
+                pause: link.property("pause").is_some(),
+                asym_pause: link.property("asym-pause").is_some(),
+                link_gpio: link.gpios("link").next(),
+            });
+        }
+
+        let legacy = self.property("fixed-link")?.value;
+        if legacy.len() != 5 * 4 {
+            return None;
+        }
+        let cell = |i: usize| BigEndianU32::from_bytes(&legacy[i * 4..]).map(|v| v.get());
+        Some(FixedLink {
+            speed: cell(2)?,
+            full_duplex: cell(1)? != 0,
+            pause: cell(3)? != 0,
+            asym_pause: cell(4)? != 0,
+            link_gpio: None,
+        })
+    }
+
+    /// `mdio` child bus of an Ethernet controller
+    pub fn mdio(self) -> Option<Mdio<'b, 'a>> {
+        self.children().find(|child| child.name.split('@').next() == Some("mdio")).map(|node| Mdio { node })
+    }
+
+    /// Available switch ports, children of `ethernet-ports` or `ports`
+    pub fn ethernet_ports(self) -> impl Iterator<Item = EthernetPort<'b, 'a>> + 'b {
+        self.children()
+            .find(|child| child.name == "ethernet-ports")
+            .or_else(|| self.children().find(|child| child.name == "ports"))
+            .into_iter()
+            .flat_map(|ports| ports.children())
+            .filter(|port| port.is_available())
+            .map(|node| EthernetPort { node })
+    }
+}
@@ -0,0 +1,254 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! GPIO consumers and controllers
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/gpio/gpio.txt
+
+use crate::node::FdtNode;
+use crate::parsing::BigEndianU32;
+use crate::phandle::{property_parts, PhandleArgs};
+
+/// Output drive of a GPIO line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpioDrive {
+    /// Drives both levels
+    PushPull,
+    /// Only drives low (`GPIO_OPEN_DRAIN`)
+    OpenDrain,
+    /// Only drives high (`GPIO_OPEN_SOURCE`)
+    OpenSource,
+}
+
+/// Bias of a GPIO line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpioBias {
+    /// Left as is
+    Default,
+    /// `GPIO_PULL_UP`
+    PullUp,
+    /// `GPIO_PULL_DOWN`
+    PullDown,
+    /// `GPIO_PULL_DISABLE`
+    Disable,
+}
+
+/// Standard flags cell of a GPIO specifier, `include/dt-bindings/gpio/gpio.h`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GpioFlags(pub u32);
+
+impl GpioFlags {
+    /// `GPIO_ACTIVE_LOW`
+    pub const ACTIVE_LOW: u32 = 1 << 0;
+    /// `GPIO_SINGLE_ENDED`
+    pub const SINGLE_ENDED: u32 = 1 << 1;
+    /// `GPIO_LINE_OPEN_DRAIN`
+    pub const LINE_OPEN_DRAIN: u32 = 1 << 2;
+    /// `GPIO_TRANSITORY`
+    pub const TRANSITORY: u32 = 1 << 3;
+    /// `GPIO_PULL_UP`
+    pub const PULL_UP: u32 = 1 << 4;
+    /// `GPIO_PULL_DOWN`
+    pub const PULL_DOWN: u32 = 1 << 5;
+    /// `GPIO_PULL_DISABLE`
+    pub const PULL_DISABLE: u32 = 1 << 6;
+
+    /// The line is asserted when low
+    pub fn active_low(self) -> bool {
+        self.0 & Self::ACTIVE_LOW != 0
+    }
+
+    /// Output drive
+    pub fn drive(self) -> GpioDrive {
+        match (self.0 & Self::SINGLE_ENDED != 0, self.0 & Self::LINE_OPEN_DRAIN != 0) {
+            (false, _) => GpioDrive::PushPull,
+            (true, true) => GpioDrive::OpenDrain,
+            (true, false) => GpioDrive::OpenSource,
+        }
+    }
+
+    /// Bias
+    pub fn bias(self) -> GpioBias {
+        if self.0 & Self::PULL_UP != 0 {
+            GpioBias::PullUp
+        } else if self.0 & Self::PULL_DOWN != 0 {
+            GpioBias::PullDown
+        } else if self.0 & Self::PULL_DISABLE != 0 {
+            GpioBias::Disable
+        } else {
+            GpioBias::Default
+        }
+    }
+
+    /// The line state may be lost across suspend or reset
+    pub fn transitory(self) -> bool {
+        self.0 & Self::TRANSITORY != 0
+    }
+}
+
+/// One GPIO line used by a node
+#[derive(Debug, Clone, Copy)]
+pub struct Gpio<'b, 'a> {
+    pub(crate) spec: PhandleArgs<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Gpio<'b, 'a> {
+    /// GPIO controller node
+    pub fn controller(&self) -> FdtNode<'b, 'a> {
+        self.spec.node
+    }
+
+    /// Line number on the controller, the first specifier cell
+    pub fn line(&self) -> u32 {
+        self.spec.args().first().copied().unwrap_or(0)
+    }
+
+    /// Flags, the second specifier cell, empty if there is none
+    pub fn flags(&self) -> GpioFlags {
+        GpioFlags(self.spec.args().get(1).copied().unwrap_or(0))
+    }
+
+    /// All specifier cells, `#gpio-cells` long
+    pub fn specifier(&self) -> &[u32] {
+        self.spec.args()
+    }
+}
+
+/// An entry of `gpio-ranges`, mapping GPIO lines to pin controller pins
+#[derive(Debug, Clone, Copy)]
+pub struct GpioRange<'b, 'a> {
+    /// Pin controller node
+    pub pinctrl: FdtNode<'b, 'a>,
+    /// First GPIO line
+    pub gpio_offset: u32,
+    /// First pin on the pin controller
+    pub pin_offset: u32,
+    /// Number of lines, 0 when the range is a pin group
+    pub count: u32,
+    /// Pin group from `gpio-ranges-group-names`, for ranges with a 0 count
+    pub group: Option<&'a str>,
+}
+
+/// Initial state of a hogged GPIO line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpioHogState {
+    /// `input`
+    Input,
+    /// `output-low`
+    OutputLow,
+    /// `output-high`
+    OutputHigh,
+}
+
+/// A `gpio-hog` child of a GPIO controller
+#[derive(Debug, Clone, Copy)]
+pub struct GpioHog<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+    cells: usize,
+}
+
+impl<'b, 'a: 'b> GpioHog<'b, 'a> {
+    /// Hogged lines from `gpios`, each `#gpio-cells` of the controller long
+    pub fn lines(&self) -> impl Iterator<Item = (u32, GpioFlags)> + 'a {
+        let value = self.node.property("gpios").map(|p| p.value).unwrap_or(&[]);
+        let cells = self.cells.max(1);
+        value.chunks_exact(cells * 4).map(|spec| {
+            let cell = |i: usize| spec.get(i * 4..).and_then(BigEndianU32::from_bytes).map(|v| v.get());
+            (cell(0).unwrap_or(0), GpioFlags(cell(1).unwrap_or(0)))
+        })
+    }
+
+    /// State the lines are put in
+    pub fn state(&self) -> Option<GpioHogState> {
+        if self.node.property("input").is_some() {
+            Some(GpioHogState::Input)
+        } else if self.node.property("output-low").is_some() {
+            Some(GpioHogState::OutputLow)
+        } else if self.node.property("output-high").is_some() {
+            Some(GpioHogState::OutputHigh)
+        } else {
+            None
+        }
+    }
+
+    /// `line-name`
+    pub fn line_name(&self) -> Option<&'a str> {
+        self.node.property("line-name")?.as_str()
+    }
+}
+
+/// Represents a node with the `gpio-controller` property
+#[derive(Debug, Clone, Copy)]
+pub struct GpioController<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> GpioController<'b, 'a> {
+    /// The controller node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// `#gpio-cells`
+    pub fn gpio_cells(&self) -> Option<usize> {
+        self.node.property("#gpio-cells")?.as_usize()
+    }
+
+    /// `ngpios`: number of lines in use
+    pub fn ngpios(&self) -> Option<u32> {
+        self.node.u32_property("ngpios")
+    }
+
+    /// `gpio-line-names`, an empty name for unnamed lines
+    pub fn line_names(&self) -> impl Iterator<Item = &'a str> + 'a {
+        self.node.strings("gpio-line-names")
+    }
+
+    /// Name of `line` from `gpio-line-names`
+    pub fn line_name(&self, line: usize) -> Option<&'a str> {
+        self.line_names().nth(line).filter(|name| !name.is_empty())
+    }
+
+    /// `gpio-ranges`
+    pub fn ranges(&self) -> impl Iterator<Item = GpioRange<'b, 'a>> + 'b {
+        let node = self.node;
+        node.phandle_args_fixed("gpio-ranges", 3).map(move |range| {
+            let [gpio_offset, pin_offset, count] = [range.args()[0], range.args()[1], range.args()[2]];
+            let group = match count {
+                0 => node.string_at("gpio-ranges-group-names", range.index).filter(|name| !name.is_empty()),
+                _ => None,
+            };
+
+            GpioRange { pinctrl: range.node, gpio_offset, pin_offset, count, group }
+        })
+    }
+
+    /// `gpio-hog` children
+    pub fn hogs(&self) -> impl Iterator<Item = GpioHog<'b, 'a>> + 'b {
+        let cells = self.gpio_cells().unwrap_or(2);
+        self.node
+            .children()
+            .filter(|child| child.property("gpio-hog").is_some() && child.is_available())
+            .map(move |node| GpioHog { node, cells })
+    }
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// GPIOs from `<name>-gpios`, or the deprecated `<name>-gpio`; an empty
+    /// `name` reads plain `gpios`
+    ///
+    /// Lines wired through a connector are resolved via its `gpio-map`.
+    pub fn gpios(self, name: &str) -> impl Iterator<Item = Gpio<'b, 'a>> + 'b {
+        let sep = if name.is_empty() { "" } else { "-" };
+        let list = property_parts(self, &[name, sep, "gpios"]).or_else(|| property_parts(self, &[name, sep, "gpio"]));
+        self.phandle_args_map_of(list, "gpio").map(|spec| Gpio { spec })
+    }
+
+    /// GPIO controller view, if the node has `gpio-controller`
+    pub fn gpio_controller(self) -> Option<GpioController<'b, 'a>> {
+        self.property("gpio-controller").map(|_| GpioController { node: self })
+    }
+}
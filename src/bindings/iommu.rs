@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! IOMMU masters and bus ID maps (`iommu-map`, `msi-map`)
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/iommu/iommu.txt
+//! and https://www.kernel.org/doc/Documentation/devicetree/bindings/pci/pci-iommu.txt
+
+use crate::node::FdtNode;
+use crate::parsing::BigEndianU32;
+use crate::phandle::{property_parts, PhandleArgs};
+
+/// An IOMMU a master is behind
+#[derive(Debug, Clone, Copy)]
+pub struct Iommu<'b, 'a> {
+    pub(crate) spec: PhandleArgs<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Iommu<'b, 'a> {
+    /// IOMMU node
+    pub fn iommu(&self) -> FdtNode<'b, 'a> {
+        self.spec.node
+    }
+
+    /// Specifier cells, `#iommu-cells` long
+    pub fn specifier(&self) -> &[u32] {
+        self.spec.args()
+    }
+
+    /// Master/stream ID, the first specifier cell
+    pub fn stream_id(&self) -> Option<u32> {
+        self.spec.args().first().copied()
+    }
+}
+
+/// A bus ID translated through a `*-map` property
+#[derive(Debug, Clone, Copy)]
+pub struct IdMapping<'b, 'a> {
+    /// Node the ID is translated for: an IOMMU or MSI controller
+    pub target: FdtNode<'b, 'a>,
+    /// Translated ID: a stream ID or MSI device ID
+    pub id: u32,
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// IOMMUs from `iommus`
+    pub fn iommus(self) -> impl Iterator<Item = Iommu<'b, 'a>> + 'b {
+        self.phandle_args("iommus", "#iommu-cells").map(|spec| Iommu { spec })
+    }
+
+    /// Translations of `id` through the `map` property, masked by
+    /// `<map>-mask`, like Linux's `of_map_id`
+    ///
+    /// Each map entry is `<id-base &target out-base length>`; every entry
+    /// covering the masked ID yields a mapping, so callers looking for a
+    /// particular target can pick theirs. Nothing is yielded when `map` is
+    /// absent.
+    pub fn map_ids(self, map: &str, id: u32) -> impl Iterator<Item = IdMapping<'b, 'a>> + 'b {
+        let mask = property_parts(self, &[map, "-mask"])
+            .and_then(|p| BigEndianU32::from_bytes(p.value))
+            .map_or(u32::MAX, |v| v.get());
+        let id = id & mask;
+        let entries = self.property(map).map(|p| p.value).unwrap_or(&[]);
+
+        entries.chunks_exact(16).filter_map(move |entry| {
+            let cell = |i: usize| BigEndianU32::from_bytes(&entry[i * 4..]).map(|v| v.get());
+            let (base, phandle, out, length) = (cell(0)?, cell(1)?, cell(2)?, cell(3)?);
+            if id < base || id - base >= length {
+                return None;
+            }
+
+            let target = self.header.find_phandle(phandle)?;
+            Some(IdMapping { target, id: (id - base).wrapping_add(out) })
+        })
+    }
+
+    /// Stream ID and IOMMU for requester ID `rid` from `iommu-map`
+    pub fn iommu_map(self, rid: u32) -> Option<IdMapping<'b, 'a>> {
+        self.map_ids("iommu-map", rid).next()
+    }
+
+    /// Device ID and MSI controller for requester ID `rid` from `msi-map`
+    pub fn msi_map(self, rid: u32) -> Option<IdMapping<'b, 'a>> {
+        self.map_ids("msi-map", rid).next()
+    }
+}
@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Device bindings shared by many nodes
+pub mod clock;
+pub mod ethernet;
+pub mod gpio;
+pub mod iommu;
+pub mod nvmem;
+pub mod opp;
+pub mod pinctrl;
+pub mod power_domain;
+pub mod regulator;
+pub mod reset;
+
+pub use clock::{AssignedClock, Clock};
+pub use ethernet::{EthernetPort, FixedLink, Mdio, Phy, PhyMode};
+pub use gpio::{Gpio, GpioBias, GpioController, GpioDrive, GpioFlags, GpioHog, GpioHogState, GpioRange};
+pub use iommu::{IdMapping, Iommu};
+pub use nvmem::{MacAddress, NvmemCell};
+pub use opp::{LegacyOpp, Opp, OppTable, OppVoltage};
+pub use pinctrl::{PinBias, PinConfig, PinctrlState};
+pub use power_domain::PowerDomain;
+pub use regulator::{Regulator, Supply};
+pub use reset::Reset;
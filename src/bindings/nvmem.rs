@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! NVMEM cell consumers and MAC addresses
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/nvmem/nvmem-consumer.yaml
+//! and https://www.kernel.org/doc/Documentation/devicetree/bindings/nvmem/layouts/fixed-layout.yaml
+
+use crate::node::FdtNode;
+use crate::parsing::BigEndianU32;
+use crate::phandle::PhandleArgs;
+
+/// A cell of an NVMEM provider used by a node
+#[derive(Debug, Clone, Copy)]
+pub struct NvmemCell<'b, 'a> {
+    /// Name from `nvmem-cell-names`, if given
+    pub name: Option<&'a str>,
+    pub(crate) spec: PhandleArgs<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> NvmemCell<'b, 'a> {
+    /// The cell node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.spec.node
+    }
+
+    /// NVMEM device holding the cell, skipping a `fixed-layout` container
+    pub fn provider(&self) -> Option<FdtNode<'b, 'a>> {
+        let parent = self.spec.node.parent()?;
+        if parent.compatible().is_some_and(|c| c.all().any(|c| c == "fixed-layout")) {
+            parent.parent()
+        } else {
+            Some(parent)
+        }
+    }
+
+    /// Byte offset of the cell in the device, from `reg`
+    pub fn offset(&self) -> Option<u64> {
+        Some(self.spec.node.reg()?.next_raw()?.0)
+    }
+
+    /// Size of the cell in bytes, from `reg`
+    pub fn size(&self) -> Option<u64> {
+        Some(self.spec.node.reg()?.next_raw()?.1)
+    }
+
+    /// `bits`: bit offset in the first byte and length in bits, for cells
+    /// that aren't byte aligned
+    pub fn bits(&self) -> Option<(u32, u32)> {
+        let bits = self.spec.node.property("bits")?.value;
+        let cell = |i: usize| BigEndianU32::from_bytes(bits.get(i * 4..)?).map(|v| v.get());
+        Some((cell(0)?, cell(1)?))
+    }
+
+    /// Specifier cells, `#nvmem-cell-cells` long, e.g. the index added to a
+    /// `mac-base` cell
+    pub fn specifier(&self) -> &[u32] {
+        self.spec.args()
+    }
+
+    /// Index of the cell in the `nvmem-cells` property
+    pub fn index(&self) -> usize {
+        self.spec.index
+    }
+}
+
+/// Where the MAC address of a network device comes from
+#[derive(Debug, Clone, Copy)]
+pub enum MacAddress<'b, 'a> {
+    /// Given in the devicetree
+    Fixed([u8; 6]),
+    /// Stored in the `mac-address` NVMEM cell, to be read from the device
+    Nvmem(NvmemCell<'b, 'a>),
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// NVMEM cells from `nvmem-cells`, named by `nvmem-cell-names`
+    pub fn nvmem_cells(self) -> impl Iterator<Item = NvmemCell<'b, 'a>> + 'b {
+        self.phandle_args_optional("nvmem-cells", "#nvmem-cell-cells")
+            .map(move |spec| NvmemCell { name: self.string_at("nvmem-cell-names", spec.index), spec })
+    }
+
+    /// NVMEM cell listed as `name` in `nvmem-cell-names`
+    pub fn nvmem_cell(self, name: &str) -> Option<NvmemCell<'b, 'a>> {
+        let index = self.string_index("nvmem-cell-names", name)?;
+        self.nvmem_cells().find(|cell| cell.index() == index)
+    }
+
+    /// MAC address, like Linux's `of_get_mac_address`: the first valid
+    /// unicast address of `mac-address`, `local-mac-address` and `address`,
+    /// or else the `mac-address` NVMEM cell
+    pub fn mac_address(self) -> Option<MacAddress<'b, 'a>> {
+        ["mac-address", "local-mac-address", "address"]
+            .iter()
+            .filter_map(|name| <[u8; 6]>::try_from(self.property(name)?.value).ok())
+            .find(|mac| mac[0] & 1 == 0 && mac.iter().any(|&b| b != 0))
+            .map(MacAddress::Fixed)
+            .or_else(|| self.nvmem_cell("mac-address").map(MacAddress::Nvmem))
+    }
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Operating Performance Points
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/opp/opp-v2-base.yaml
+//! and https://www.kernel.org/doc/Documentation/devicetree/bindings/opp/opp-v1.yaml
+
+use crate::node::FdtNode;
+use crate::parsing::BigEndianU64;
+use crate::standard_nodes::Cpu;
+
+/// Voltage of one supply at an OPP, in microvolts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OppVoltage {
+    /// Target voltage
+    pub target: u32,
+    /// Lowest acceptable voltage
+    pub min: u32,
+    /// Highest acceptable voltage
+    pub max: u32,
+}
+
+/// An entry of the legacy `operating-points` property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LegacyOpp {
+    /// Frequency in kHz
+    pub frequency_khz: u32,
+    /// Voltage in microvolts
+    pub microvolt: u32,
+}
+
+/// An `operating-points-v2` table
+#[derive(Debug, Clone, Copy)]
+pub struct OppTable<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> OppTable<'b, 'a> {
+    /// The table node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// `opp-shared`: all devices using the table switch together
+    pub fn shared(&self) -> bool {
+        self.node.property("opp-shared").is_some()
+    }
+
+    /// Available OPPs, in node order
+    pub fn opps(&self) -> impl Iterator<Item = Opp<'b, 'a>> + 'b {
+        self.node.children().filter(|node| node.is_available()).map(|node| Opp { node })
+    }
+
+    /// The OPP to use during system suspend
+    pub fn suspend_opp(&self) -> Option<Opp<'b, 'a>> {
+        self.opps().find(|opp| opp.suspend())
+    }
+}
+
+/// An OPP node of an [`OppTable`]
+#[derive(Debug, Clone, Copy)]
+pub struct Opp<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Opp<'b, 'a> {
+    /// `opp-hz` of the first clock
+    pub fn hz(&self) -> Option<u64> {
+        self.rates().next()
+    }
+
+    /// `opp-hz` of every clock of devices with several
+    pub fn rates(&self) -> impl Iterator<Item = u64> + 'a {
+        let value = self.node.property("opp-hz").map(|p| p.value).unwrap_or(&[]);
+        value.chunks_exact(8).filter_map(|rate| BigEndianU64::from_bytes(rate).map(|v| v.get()))
+    }
+
+    /// `opp-microvolt` for a device with `supplies` regulators
+    ///
+    /// The property holds either one target voltage or a target/min/max
+    /// triplet per supply; nothing is yielded if its length matches neither.
+    pub fn microvolt(&self, supplies: usize) -> impl Iterator<Item = OppVoltage> + 'a {
+        voltages(self.node.property("opp-microvolt").map(|p| p.value).unwrap_or(&[]), supplies)
+    }
+
+    /// `opp-microvolt-<name>`, which replaces `opp-microvolt` for devices
+    /// using the `<name>` variant of the table
+    pub fn microvolt_named(&self, name: &str, supplies: usize) -> impl Iterator<Item = OppVoltage> + 'a {
+        let property = self.node.properties().find(|p| p.name.strip_prefix("opp-microvolt-") == Some(name));
+        voltages(property.map(|p| p.value).unwrap_or(&[]), supplies)
+    }
+
+    /// `opp-microamp`, one value per supply
+    pub fn microamp(&self) -> impl Iterator<Item = u32> + 'a {
+        cells(self.node.property("opp-microamp").map(|p| p.value).unwrap_or(&[]))
+    }
+
+    /// `clock-latency-ns`: time to switch to this OPP
+    pub fn clock_latency_ns(&self) -> Option<u32> {
+        self.node.u32_property("clock-latency-ns")
+    }
+
+    /// `turbo-mode`: only usable for short periods
+    pub fn turbo_mode(&self) -> bool {
+        self.node.property("turbo-mode").is_some()
+    }
+
+    /// `opp-suspend`: the OPP to use during system suspend
+    pub fn suspend(&self) -> bool {
+        self.node.property("opp-suspend").is_some()
+    }
+
+    /// `required-opps`: OPPs of other tables this one depends on
+    pub fn required_opps(&self) -> impl Iterator<Item = Opp<'b, 'a>> + 'b {
+        self.node.phandle_args_fixed("required-opps", 0).map(|opp| Opp { node: opp.node })
+    }
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// OPP table `operating-points-v2` points at, whatever its compatible
+    pub fn opp_table(self) -> Option<OppTable<'b, 'a>> {
+        self.phandle_args_fixed("operating-points-v2", 0).next().map(|table| OppTable { node: table.node })
+    }
+
+    /// Legacy `operating-points` as `<kHz uV>` pairs
+    pub fn operating_points(self) -> impl Iterator<Item = LegacyOpp> + 'a {
+        let value = self.property("operating-points").map(|p| p.value).unwrap_or(&[]);
+        value.chunks_exact(8).map(|pair| {
+            let mut pair = cells(pair);
+            LegacyOpp { frequency_khz: pair.next().unwrap_or(0), microvolt: pair.next().unwrap_or(0) }
+        })
+    }
+}
+
+impl<'b, 'a: 'b> Cpu<'b, 'a> {
+    /// OPP table of the CPU from `operating-points-v2`
+    pub fn opp_table(self) -> Option<OppTable<'b, 'a>> {
+        self.node.opp_table()
+    }
+}
+
+fn cells(value: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    value.chunks_exact(4).map(|cell| u32::from_be_bytes([cell[0], cell[1], cell[2], cell[3]]))
+}
+
+fn voltages(value: &[u8], supplies: usize) -> impl Iterator<Item = OppVoltage> + '_ {
+    let supplies = supplies.max(1);
+    let (per_supply, count) = match value.len() / 4 {
+        n if n == supplies => (1, supplies),
+        n if n == supplies * 3 => (3, supplies),
+        _ => (1, 0),
+    };
+
+    value.chunks_exact(per_supply * 4).take(count).map(|supply| {
+        let mut cells = cells(supply);
+        let target = cells.next().unwrap_or(0);
+        OppVoltage { target, min: cells.next().unwrap_or(target), max: cells.next().unwrap_or(target) }
+    })
+}
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Pin control client states and generic pin configuration
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/pinctrl/pinctrl-bindings.txt
+
+use crate::node::FdtNode;
+use crate::parsing::BigEndianU32;
+
+/// Bias of the pins of a [`PinConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinBias {
+    /// `bias-disable`
+    Disable,
+    /// `bias-high-impedance`
+    HighImpedance,
+    /// `bias-bus-hold`
+    BusHold,
+    /// `bias-pull-up`, with the resistance in ohms if given
+    PullUp(Option<u32>),
+    /// `bias-pull-down`, with the resistance in ohms if given
+    PullDown(Option<u32>),
+    /// `bias-pull-pin-default`
+    PullPinDefault,
+}
+
+/// A pin configuration node referenced by a `pinctrl-N` property
+#[derive(Debug, Clone, Copy)]
+pub struct PinConfig<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> PinConfig<'b, 'a> {
+    /// `pins`: names of the pins configured
+    pub fn pins(&self) -> impl Iterator<Item = &'a str> + 'a {
+        self.node.strings("pins")
+    }
+
+    /// `groups`: names of the pin groups configured
+    pub fn groups(&self) -> impl Iterator<Item = &'a str> + 'a {
+        self.node.strings("groups")
+    }
+
+    /// `function` to mux onto the pins
+    pub fn function(&self) -> Option<&'a str> {
+        self.node.property("function")?.as_str()
+    }
+
+    /// Bias, from the first `bias-*` property present
+    pub fn bias(&self) -> Option<PinBias> {
+        self.node.properties().find_map(|p| {
+            let ohms = BigEndianU32::from_bytes(p.value).map(|v| v.get());
+            Some(match p.name {
+                "bias-disable" => PinBias::Disable,
+                "bias-high-impedance" => PinBias::HighImpedance,
+                "bias-bus-hold" => PinBias::BusHold,
+                "bias-pull-up" => PinBias::PullUp(ohms),
+                "bias-pull-down" => PinBias::PullDown(ohms),
+                "bias-pull-pin-default" => PinBias::PullPinDefault,
+                _ => return None,
+            })
+        })
+    }
+
+    /// `drive-strength` in mA
+    pub fn drive_strength(&self) -> Option<u32> {
+        self.node.u32_property("drive-strength")
+    }
+
+    /// `drive-strength-microamp` in uA
+    pub fn drive_strength_microamp(&self) -> Option<u32> {
+        self.node.u32_property("drive-strength-microamp")
+    }
+
+    /// `Some(true)` for `input-enable`, `Some(false)` for `input-disable`
+    pub fn input_enable(&self) -> Option<bool> {
+        if self.node.property("input-enable").is_some() {
+            Some(true)
+        } else if self.node.property("input-disable").is_some() {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Child configuration nodes, for controllers that split a state into
+    /// several groups of pins
+    pub fn subconfigs(&self) -> impl Iterator<Item = PinConfig<'b, 'a>> + 'b {
+        self.node.children().map(|node| PinConfig { node })
+    }
+}
+
+/// A pin control state of a client node, from `pinctrl-N`
+#[derive(Debug, Clone, Copy)]
+pub struct PinctrlState<'b, 'a> {
+    /// State number `N`
+    pub id: usize,
+    /// Name from `pinctrl-names`, if given
+    pub name: Option<&'a str>,
+    phandles: &'a [u8],
+    node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> PinctrlState<'b, 'a> {
+    /// Configuration nodes of the state, in order; an empty state has none
+    ///
+    /// Iteration stops at the first phandle that doesn't resolve.
+    pub fn configs(&self) -> impl Iterator<Item = PinConfig<'b, 'a>> + 'b {
+        let header = self.node.header;
+        self.phandles
+            .chunks_exact(4)
+            .map_while(move |phandle| header.find_phandle(BigEndianU32::from_bytes(phandle)?.get()))
+            .map(|node| PinConfig { node })
+    }
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// Pin control states from `pinctrl-0`, `pinctrl-1`, ... named by
+    /// `pinctrl-names`
+    ///
+    /// Like Linux, the states end at the first missing number.
+    pub fn pinctrl_states(self) -> impl Iterator<Item = PinctrlState<'b, 'a>> + 'b {
+        (0..).map_while(move |id| {
+            let prop = self.properties().find(|p| {
+                p.name.strip_prefix("pinctrl-").and_then(|n| n.parse::<usize>().ok()) == Some(id)
+            })?;
+
+            Some(PinctrlState { id, name: self.string_at("pinctrl-names", id), phandles: prop.value, node: self })
+        })
+    }
+
+    /// Pin control state listed as `name` in `pinctrl-names`
+    pub fn pinctrl_state(self, name: &str) -> Option<PinctrlState<'b, 'a>> {
+        self.pinctrl_states().find(|state| state.name == Some(name))
+    }
+}
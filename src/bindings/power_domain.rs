@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Power domain consumers
+//!
+//! Reference: https://github.com/devicetree-org/dt-schema/blob/main/dtschema/schemas/power-domain/power-domain-consumer.yaml
+
+use crate::node::FdtNode;
+use crate::phandle::PhandleArgs;
+
+/// A power domain a node belongs to
+#[derive(Debug, Clone, Copy)]
+pub struct PowerDomain<'b, 'a> {
+    /// Name from `power-domain-names`, if given
+    pub name: Option<&'a str>,
+    pub(crate) spec: PhandleArgs<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> PowerDomain<'b, 'a> {
+    /// Power domain provider node
+    pub fn provider(&self) -> FdtNode<'b, 'a> {
+        self.spec.node
+    }
+
+    /// Specifier cells, `#power-domain-cells` long
+    pub fn specifier(&self) -> &[u32] {
+        self.spec.args()
+    }
+
+    /// Index of the domain in the `power-domains` property
+    pub fn index(&self) -> usize {
+        self.spec.index
+    }
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// Power domains from `power-domains`, named by `power-domain-names`
+    pub fn power_domains(self) -> impl Iterator<Item = PowerDomain<'b, 'a>> + 'b {
+        self.phandle_args("power-domains", "#power-domain-cells")
+            .map(move |spec| PowerDomain { name: self.string_at("power-domain-names", spec.index), spec })
+    }
+
+    /// Power domain listed as `name` in `power-domain-names`
+    pub fn power_domain(self, name: &str) -> Option<PowerDomain<'b, 'a>> {
+        let index = self.string_index("power-domain-names", name)?;
+        self.power_domains().find(|domain| domain.index() == index)
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Regulator consumers and regulator nodes
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/regulator/regulator.yaml
+
+use crate::bindings::Gpio;
+use crate::node::FdtNode;
+use crate::parsing::BigEndianU32;
+use crate::phandle::property_parts;
+
+/// A `<name>-supply` of a consumer node
+#[derive(Debug, Clone, Copy)]
+pub struct Supply<'b, 'a> {
+    /// Supply name, the property name without `-supply`
+    pub name: &'a str,
+    /// Regulator feeding the supply
+    pub regulator: Regulator<'b, 'a>,
+}
+
+/// Represents a regulator node with specific helper methods
+#[derive(Debug, Clone, Copy)]
+pub struct Regulator<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Regulator<'b, 'a> {
+    /// Wraps a regulator node
+    pub fn new(node: FdtNode<'b, 'a>) -> Self {
+        Self { node }
+    }
+
+    /// The regulator node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// `regulator-name`
+    pub fn name(&self) -> Option<&'a str> {
+        self.node.property("regulator-name")?.as_str()
+    }
+
+    /// `regulator-min-microvolt`
+    pub fn min_microvolt(&self) -> Option<u32> {
+        self.node.u32_property("regulator-min-microvolt")
+    }
+
+    /// `regulator-max-microvolt`
+    pub fn max_microvolt(&self) -> Option<u32> {
+        self.node.u32_property("regulator-max-microvolt")
+    }
+
+    /// `regulator-min-microamp`
+    pub fn min_microamp(&self) -> Option<u32> {
+        self.node.u32_property("regulator-min-microamp")
+    }
+
+    /// `regulator-max-microamp`
+    pub fn max_microamp(&self) -> Option<u32> {
+        self.node.u32_property("regulator-max-microamp")
+    }
+
+    /// `regulator-always-on`: must never be turned off
+    pub fn always_on(&self) -> bool {
+        self.node.property("regulator-always-on").is_some()
+    }
+
+    /// `regulator-boot-on`: left enabled by the bootloader
+    pub fn boot_on(&self) -> bool {
+        self.node.property("regulator-boot-on").is_some()
+    }
+
+    /// Whether the node is a `regulator-fixed`
+    pub fn is_fixed(&self) -> bool {
+        self.node.compatible().is_some_and(|c| c.all().any(|c| c == "regulator-fixed"))
+    }
+
+    /// Output voltage of a `regulator-fixed`, whose min and max must agree
+    pub fn fixed_microvolt(&self) -> Option<u32> {
+        let microvolt = self.min_microvolt()?;
+        (self.is_fixed() && self.max_microvolt() == Some(microvolt)).then_some(microvolt)
+    }
+
+    /// Enable line from `gpios`, or the deprecated `gpio`
+    pub fn enable_gpio(&self) -> Option<Gpio<'b, 'a>> {
+        self.node.gpios("").next()
+    }
+
+    /// `enable-active-high`: the enable line is asserted when high
+    pub fn enable_active_high(&self) -> bool {
+        self.node.property("enable-active-high").is_some()
+    }
+
+    /// `startup-delay-us`
+    pub fn startup_delay_us(&self) -> Option<u32> {
+        self.node.u32_property("startup-delay-us")
+    }
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// Supplies from the `*-supply` properties
+    pub fn supplies(self) -> impl Iterator<Item = Supply<'b, 'a>> + 'b {
+        self.properties().filter_map(move |p| {
+            let name = p.name.strip_suffix("-supply")?;
+            let node = self.header.find_phandle(BigEndianU32::from_bytes(p.value)?.get())?;
+            Some(Supply { name, regulator: Regulator { node } })
+        })
+    }
+
+    /// Regulator of the `<name>-supply` property
+    pub fn supply(self, name: &str) -> Option<Regulator<'b, 'a>> {
+        let phandle = BigEndianU32::from_bytes(property_parts(self, &[name, "-supply"])?.value)?.get();
+        self.header.find_phandle(phandle).map(|node| Regulator { node })
+    }
+}
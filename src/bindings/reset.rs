@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Reset consumers
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/reset/reset.txt
+
+use crate::node::FdtNode;
+use crate::phandle::PhandleArgs;
+
+/// A reset line of a node
+#[derive(Debug, Clone, Copy)]
+pub struct Reset<'b, 'a> {
+    /// Name from `reset-names`, if given
+    pub name: Option<&'a str>,
+    pub(crate) spec: PhandleArgs<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Reset<'b, 'a> {
+    /// Reset controller node
+    pub fn controller(&self) -> FdtNode<'b, 'a> {
+        self.spec.node
+    }
+
+    /// Specifier cells, `#reset-cells` long
+    pub fn specifier(&self) -> &[u32] {
+        self.spec.args()
+    }
+
+    /// Index of the reset in the `resets` property
+    pub fn index(&self) -> usize {
+        self.spec.index
+    }
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// Reset lines from `resets`, named by `reset-names`
+    pub fn resets(self) -> impl Iterator<Item = Reset<'b, 'a>> + 'b {
+        self.phandle_args("resets", "#reset-cells")
+            .map(move |spec| Reset { name: self.string_at("reset-names", spec.index), spec })
+    }
+
+    /// Reset line listed as `name` in `reset-names`
+    pub fn reset(self, name: &str) -> Option<Reset<'b, 'a>> {
+        let index = self.string_index("reset-names", name)?;
+        self.resets().find(|reset| reset.index() == index)
+    }
+}
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Sequential FDT blob writer
+
+use alloc::vec::Vec;
+
+use crate::{
+    error::FdtError,
+    header::{FDT_HEADER_SIZE, FDT_LAST_COMP_VERSION, FDT_MAGIC, FDT_VERSION},
+    node::{FDT_BEGIN_NODE, FDT_END, FDT_END_NODE, FDT_PROP},
+};
+
+/// Builds a flattened devicetree blob node by node
+///
+/// Nodes are opened with [`FdtBuilder::begin_node`] and closed with
+/// [`FdtBuilder::end_node`]; the first node is the root and must be named
+/// `""`. Properties of a node have to be added before its first subnode.
+/// The produced blob uses the same layout as `dtc`: header, memory
+/// reservation block, structure block and strings block.
+#[derive(Debug, Clone, Default)]
+pub struct FdtBuilder {
+    mem_rsv: Vec<(u64, u64)>,
+    structs: Vec<u8>,
+    strings: Vec<u8>,
+    boot_cpuid_phys: u32,
+    depth: usize,
+    in_props: bool,
+    root_done: bool,
+}
+
+impl FdtBuilder {
+    /// Creates an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `boot_cpuid_phys` header field
+    pub fn boot_cpuid_phys(&mut self, cpuid: u32) {
+        self.boot_cpuid_phys = cpuid;
+    }
+
+    /// Appends an entry to the memory reservation block
+    pub fn add_mem_rsv(&mut self, address: u64, size: u64) {
+        self.mem_rsv.push((address, size));
+    }
+
+    /// Opens a node named `name` below the currently open node
+    pub fn begin_node(&mut self, name: &str) -> Result<(), FdtError> {
+        if self.root_done || (self.depth == 0 && !name.is_empty()) {
+            return Err(FdtError::BadState);
+        }
+
+        self.push_u32(FDT_BEGIN_NODE);
+        self.structs.extend_from_slice(name.as_bytes());
+        self.structs.push(0);
+        self.align();
+
+        self.depth += 1;
+        self.in_props = true;
+
+        Ok(())
+    }
+
+    /// Adds a property to the currently open node
+    pub fn property(&mut self, name: &str, value: &[u8]) -> Result<(), FdtError> {
+        if self.depth == 0 || !self.in_props {
+            return Err(FdtError::BadState);
+        }
+
+        let name_offset = self.string_offset(name);
+        self.push_u32(FDT_PROP);
+        self.push_u32(value.len() as u32);
+        self.push_u32(name_offset);
+        self.structs.extend_from_slice(value);
+        self.align();
+
+        Ok(())
+    }
+
+    /// Closes the currently open node
+    pub fn end_node(&mut self) -> Result<(), FdtError> {
+        if self.depth == 0 {
+            return Err(FdtError::BadState);
+        }
+
+        self.push_u32(FDT_END_NODE);
+        self.depth -= 1;
+        self.in_props = false;
+        self.root_done = self.depth == 0;
+
+        Ok(())
+    }
+
+    /// Lays out the final blob
+    pub fn finish(mut self) -> Result<Vec<u8>, FdtError> {
+        if !self.root_done {
+            return Err(FdtError::BadState);
+        }
+
+        self.push_u32(FDT_END);
+
+        let off_mem_rsvmap = FDT_HEADER_SIZE;
+        let off_dt_struct = off_mem_rsvmap + (self.mem_rsv.len() + 1) * 16;
+        let off_dt_strings = off_dt_struct + self.structs.len();
+        let totalsize = off_dt_strings + self.strings.len();
+
+        let mut blob = Vec::with_capacity(totalsize);
+        for field in [
+            FDT_MAGIC,
+            totalsize as u32,
+            off_dt_struct as u32,
+            off_dt_strings as u32,
+            off_mem_rsvmap as u32,
+            FDT_VERSION,
+            FDT_LAST_COMP_VERSION,
+            self.boot_cpuid_phys,
+            self.strings.len() as u32,
+            self.structs.len() as u32,
+        ] {
+            blob.extend_from_slice(&field.to_be_bytes());
+        }
+
+        for (address, size) in self.mem_rsv.iter().chain(core::iter::once(&(0, 0))) {
+            blob.extend_from_slice(&address.to_be_bytes());
+            blob.extend_from_slice(&size.to_be_bytes());
+        }
+
+        blob.extend_from_slice(&self.structs);
+        blob.extend_from_slice(&self.strings);
+
+        Ok(blob)
+    }
+
+    fn push_u32(&mut self, value: u32) {
+        self.structs.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn align(&mut self) {
+        let len = (self.structs.len() + 3) & !0x3;
+        self.structs.resize(len, 0);
+    }
+
+    /// Like `dtc`, reuses any existing string (suffixes included) before
+    /// appending a new one
+    fn string_offset(&mut self, name: &str) -> u32 {
+        let name = name.as_bytes();
+        let found = self
+            .strings
+            .windows(name.len() + 1)
+            .position(|w| &w[..name.len()] == name && w[name.len()] == 0);
+
+        match found {
+            Some(offset) => offset as u32,
+            None => {
+                let offset = self.strings.len();
+                self.strings.extend_from_slice(name);
+                self.strings.push(0);
+                offset as u32
+            }
+        }
+    }
+}
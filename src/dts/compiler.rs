@@ -0,0 +1,330 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! DTS to DTB compiler
+
+use alloc::{collections::BTreeSet, format, string::String, vec::Vec};
+
+use super::parser::Parser;
+use super::tree::{Node, Property, RefKind, Tree};
+use crate::builder::FdtBuilder;
+
+/// Error raised while compiling devicetree source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DtsError {
+    /// File the error was found in
+    pub file: String,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number
+    pub column: usize,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl core::fmt::Display for DtsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}:{}: {}", self.file, self.line, self.column, self.message)
+    }
+}
+
+/// Maps an `/include/` path, as written in the source, to the file contents
+pub type IncludeResolver<'r> = dyn FnMut(&str) -> Option<String> + 'r;
+
+/// Compiles devicetree source into a flattened devicetree blob
+///
+/// Supports the `dtc` source syntax: `/dts-v1/`, `/memreserve/`, labels,
+/// `&label` / `&{/path}` references (phandles are generated for referenced
+/// nodes without one), cell arrays including `/bits/` and integer
+/// expressions, byte strings, string lists, `/delete-node/`,
+/// `/delete-property/`, `/omit-if-no-ref/`, `/include/` and merging of
+/// repeated node definitions.
+///
+/// `/include/` paths are handed to the resolver set with
+/// [`DtsCompiler::include_resolver`]; without one, and with the `std`
+/// feature, they are read from the filesystem relative to the including
+/// file.
+pub struct DtsCompiler<'r> {
+    symbols: bool,
+    local_fixups: bool,
+    resolver: Option<&'r mut IncludeResolver<'r>>,
+}
+
+impl Default for DtsCompiler<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'r> DtsCompiler<'r> {
+    /// Creates a compiler with the same defaults as `dtc`
+    pub fn new() -> Self {
+        Self { symbols: false, local_fixups: false, resolver: None }
+    }
+
+    /// Generates the `/__symbols__` node, like `dtc -@`
+    pub fn symbols(mut self, enable: bool) -> Self {
+        self.symbols = enable;
+        self
+    }
+
+    /// Generates the `/__local_fixups__` node recording where phandle
+    /// references were resolved
+    pub fn local_fixups(mut self, enable: bool) -> Self {
+        self.local_fixups = enable;
+        self
+    }
+
+    /// Resolves `/include/` paths to the contents of the included file
+    pub fn include_resolver(mut self, resolver: &'r mut IncludeResolver<'r>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Compiles `source`
+    pub fn compile(&mut self, source: &str) -> Result<Vec<u8>, DtsError> {
+        self.compile_named("<source>", String::from(source))
+    }
+
+    /// Compiles the file at `path`
+    #[cfg(feature = "std")]
+    pub fn compile_file(&mut self, path: impl AsRef<std::path::Path>) -> Result<Vec<u8>, DtsError> {
+        let path = path.as_ref();
+        let name = format!("{}", path.display());
+        let text = std::fs::read_to_string(path).map_err(|e| DtsError {
+            file: name.clone(),
+            line: 0,
+            column: 0,
+            message: format!("couldn't read file: {}", e),
+        })?;
+
+        self.compile_named(&name, text)
+    }
+
+    fn compile_named(&mut self, name: &str, text: String) -> Result<Vec<u8>, DtsError> {
+        let resolver = &mut self.resolver;
+        let mut loader = |_from: &str, path: &str| -> Option<(String, String)> {
+            if let Some(resolver) = resolver {
+                return resolver(path).map(|text| (String::from(path), text));
+            }
+
+            #[cfg(feature = "std")]
+            {
+                let dir = std::path::Path::new(_from).parent().unwrap_or(std::path::Path::new(""));
+                let full = dir.join(path);
+                let text = std::fs::read_to_string(&full).ok()?;
+                Some((format!("{}", full.display()), text))
+            }
+
+            #[cfg(not(feature = "std"))]
+            None
+        };
+
+        let mut tree = Parser::new(String::from(name), text, &mut loader).parse()?;
+
+        check_labels(&tree.root)?;
+        resolve_phandles(&mut tree.root)?;
+        resolve_paths(&mut tree.root)?;
+        tree.root.remove_unreferenced();
+
+        if self.symbols {
+            add_symbols(&mut tree.root);
+        }
+        if self.local_fixups {
+            add_local_fixups(&mut tree.root);
+        }
+
+        emit(&tree).map_err(|_| DtsError {
+            file: String::from(name),
+            line: 0,
+            column: 0,
+            message: String::from("failed to lay out the blob"),
+        })
+    }
+}
+
+fn check_labels(root: &Node) -> Result<(), DtsError> {
+    let mut result = Ok(());
+    root.walk(&mut |node, _| {
+        for label in &node.labels {
+            let owner = root.find_label(&label.name).map(|p| root.at(&p) as *const Node);
+            if result.is_ok() && owner != Some(node as *const Node) {
+                result = Err(label.location.error(format!("duplicate label '{}'", label.name)));
+            }
+        }
+    });
+
+    result
+}
+
+/// Fills every `<&ref>` cell with the phandle of its target, allocating
+/// phandles as needed
+fn resolve_phandles(root: &mut Node) -> Result<(), DtsError> {
+    let mut used = BTreeSet::new();
+    let mut refs = Vec::new();
+    root.walk(&mut |node, path| {
+        for name in ["phandle", "linux,phandle"] {
+            if let Some(value) = node.property(name).and_then(|p| <[u8; 4]>::try_from(&p.value[..]).ok()) {
+                used.insert(u32::from_be_bytes(value));
+            }
+        }
+
+        for (pi, prop) in node.props.iter().enumerate() {
+            for (ri, r) in prop.refs.iter().enumerate() {
+                if r.kind == RefKind::Phandle {
+                    refs.push((path.to_vec(), pi, ri));
+                }
+            }
+        }
+    });
+
+    let mut next = 1;
+    for (path, pi, ri) in refs {
+        let prop = &root.at(&path).props[pi];
+        let r = &prop.refs[ri];
+        let Some(target) = root.resolve(&r.target) else {
+            return Err(r.location.error("reference to non-existent node or label"));
+        };
+        let offset = r.offset;
+
+        let node = root.at_mut(&target);
+        node.referenced = true;
+        // like dtc, a legacy `linux,phandle` is reused as is
+        let existing = ["phandle", "linux,phandle"]
+            .iter()
+            .find_map(|name| <[u8; 4]>::try_from(&node.property(name)?.value[..]).ok())
+            .map(u32::from_be_bytes)
+            .filter(|&ph| ph != 0 && ph != u32::MAX);
+
+        let phandle = match existing {
+            Some(phandle) => phandle,
+            None => {
+                while used.contains(&next) {
+                    next += 1;
+                }
+                used.insert(next);
+                node.props.push(Property {
+                    name: String::from("phandle"),
+                    value: next.to_be_bytes().to_vec(),
+                    refs: Vec::new(),
+                });
+                next
+            }
+        };
+
+        root.at_mut(&path).props[pi].value[offset..offset + 4].copy_from_slice(&phandle.to_be_bytes());
+    }
+
+    Ok(())
+}
+
+/// Inserts the full path of the target of every bare `&ref`
+fn resolve_paths(root: &mut Node) -> Result<(), DtsError> {
+    let mut props = Vec::new();
+    root.walk(&mut |node, path| {
+        for (pi, prop) in node.props.iter().enumerate() {
+            if prop.refs.iter().any(|r| r.kind == RefKind::Path) {
+                props.push((path.to_vec(), pi));
+            }
+        }
+    });
+
+    for (path, pi) in props {
+        for ri in 0..root.at(&path).props[pi].refs.len() {
+            let r = root.at(&path).props[pi].refs[ri].clone();
+            if r.kind != RefKind::Path {
+                continue;
+            }
+
+            let Some(target) = root.resolve(&r.target) else {
+                return Err(r.location.error("reference to non-existent node or label"));
+            };
+            root.at_mut(&target).referenced = true;
+
+            let mut full = root.full_path(&target).into_bytes();
+            full.push(0);
+
+            // everything referenced later in the value moves along
+            let prop = &mut root.at_mut(&path).props[pi];
+            prop.value.splice(r.offset..r.offset, full.iter().copied());
+            for later in &mut prop.refs[ri + 1..] {
+                later.offset += full.len();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn add_symbols(root: &mut Node) {
+    let mut symbols = Vec::new();
+    root.walk(&mut |node, path| {
+        for label in &node.labels {
+            symbols.push((label.name.clone(), path.to_vec()));
+        }
+    });
+
+    let symbols: Vec<_> = symbols.into_iter().map(|(label, path)| (label, root.full_path(&path))).collect();
+    let node = root.child_mut("__symbols__");
+    for (label, path) in symbols {
+        if node.property(&label).is_none() {
+            let mut value = path.into_bytes();
+            value.push(0);
+            node.props.push(Property { name: label, value, refs: Vec::new() });
+        }
+    }
+}
+
+fn add_local_fixups(root: &mut Node) {
+    let mut fixups = Vec::new();
+    root.walk(&mut |node, path| {
+        for prop in &node.props {
+            let offsets: Vec<u8> = prop
+                .refs
+                .iter()
+                .filter(|r| r.kind == RefKind::Phandle)
+                .flat_map(|r| (r.offset as u32).to_be_bytes())
+                .collect();
+            if !offsets.is_empty() {
+                fixups.push((path.to_vec(), prop.name.clone(), offsets));
+            }
+        }
+    });
+
+    let fixups: Vec<_> = fixups
+        .into_iter()
+        .map(|(path, prop, offsets)| {
+            let names: Vec<String> = (1..=path.len()).map(|n| root.at(&path[..n]).name.clone()).collect();
+            (names, prop, offsets)
+        })
+        .collect();
+
+    let local_fixups = root.child_mut("__local_fixups__");
+    for (names, name, value) in fixups {
+        let node = names.iter().fold(&mut *local_fixups, |node, name| node.child_mut(name));
+        node.props.push(Property { name, value, refs: Vec::new() });
+    }
+}
+
+fn emit(tree: &Tree) -> Result<Vec<u8>, crate::FdtError> {
+    fn emit_node(builder: &mut FdtBuilder, node: &Node) -> Result<(), crate::FdtError> {
+        builder.begin_node(&node.name)?;
+        for prop in &node.props {
+            builder.property(&prop.name, &prop.value)?;
+        }
+        for child in &node.children {
+            emit_node(builder, child)?;
+        }
+        builder.end_node()
+    }
+
+    let mut builder = FdtBuilder::new();
+    for &(address, size) in &tree.mem_rsv {
+        builder.add_mem_rsv(address, size);
+    }
+
+    emit_node(&mut builder, &tree.root)?;
+    builder.finish()
+}
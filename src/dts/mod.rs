@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Devicetree source (DTS) support
+
+mod writer;
+#[cfg(feature = "alloc")]
+mod compiler;
+#[cfg(feature = "alloc")]
+mod parser;
+#[cfg(feature = "alloc")]
+mod tree;
+
+pub use writer::{DtsWriter, Indent};
+#[cfg(feature = "alloc")]
+pub use compiler::{DtsCompiler, DtsError, IncludeResolver};
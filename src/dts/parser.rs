@@ -0,0 +1,636 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Recursive descent parser for the `dtc` source grammar
+
+use alloc::{format, string::String, vec::Vec};
+
+use super::tree::{Item, Label, Location, NodeDef, Property, RefKind, RefTarget, Reference, Tree};
+use super::DtsError;
+
+/// Maximum nesting of `/include/` directives
+const MAX_INCLUDE_DEPTH: usize = 32;
+
+/// Loads an included file: given the including file and the path as written,
+/// returns the resolved file name and its contents
+pub(super) type Loader<'l> = dyn FnMut(&str, &str) -> Option<(String, String)> + 'l;
+
+struct Source {
+    name: String,
+    text: String,
+    pos: usize,
+}
+
+pub(super) struct Parser<'p, 'l> {
+    sources: Vec<Source>,
+    loader: &'p mut Loader<'l>,
+}
+
+type Result<T> = core::result::Result<T, DtsError>;
+
+fn is_name_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b",._+*#?@-".contains(&c)
+}
+
+fn is_label_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+impl<'p, 'l> Parser<'p, 'l> {
+    pub fn new(name: String, text: String, loader: &'p mut Loader<'l>) -> Self {
+        Self { sources: alloc::vec![Source { name, text, pos: 0 }], loader }
+    }
+
+    fn source(&self) -> &Source {
+        self.sources.last().unwrap()
+    }
+
+    fn rest(&self) -> &[u8] {
+        let source = self.source();
+        &source.text.as_bytes()[source.pos..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.rest().first().copied()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<u8> {
+        self.rest().get(n).copied()
+    }
+
+    fn bump(&mut self, n: usize) {
+        self.sources.last_mut().unwrap().pos += n;
+    }
+
+    fn location(&self) -> Location {
+        let source = self.source();
+        let before = &source.text.as_bytes()[..source.pos];
+        let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+        let column = before.iter().rev().take_while(|&&b| b != b'\n').count() + 1;
+
+        Location { file: source.name.clone(), line, column }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T> {
+        Err(self.location().error(message))
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        self.rest().starts_with(s.as_bytes())
+    }
+
+    fn eat(&mut self, s: &str) -> Result<bool> {
+        self.skip_ws()?;
+        if self.starts_with(s) {
+            self.bump(s.len());
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn expect(&mut self, s: &str) -> Result<()> {
+        if !self.eat(s)? {
+            return self.error(format!("expected `{}`", s));
+        }
+
+        Ok(())
+    }
+
+    /// Skips whitespace, comments and `cpp` line markers, and follows
+    /// `/include/` directives
+    fn skip_ws(&mut self) -> Result<()> {
+        loop {
+            let at_line_start = {
+                let source = self.source();
+                source.pos == 0 || source.text.as_bytes()[source.pos - 1] == b'\n'
+            };
+
+            match self.peek() {
+                None if self.sources.len() > 1 => {
+                    self.sources.pop();
+                }
+                Some(c) if c.is_ascii_whitespace() => self.bump(1),
+                Some(b'/') if self.peek_at(1) == Some(b'/') => self.skip_line(),
+                Some(b'/') if self.peek_at(1) == Some(b'*') => {
+                    let Some(end) = self.rest().windows(2).skip(2).position(|w| w == b"*/") else {
+                        return self.error("unterminated comment");
+                    };
+                    self.bump(end + 4);
+                }
+                Some(b'#')
+                    if at_line_start
+                        && (self.starts_with("#line")
+                            || (self.peek_at(1) == Some(b' ')
+                                && self.peek_at(2).is_some_and(|c| c.is_ascii_digit()))) =>
+                {
+                    self.skip_line()
+                }
+                Some(b'/') if self.starts_with("/include/") => {
+                    self.bump("/include/".len());
+                    self.include()?;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        let n = self.rest().iter().position(|&b| b == b'\n').unwrap_or(self.rest().len());
+        self.bump(n);
+    }
+
+    fn include(&mut self) -> Result<()> {
+        self.skip_ws()?;
+        if self.peek() != Some(b'"') {
+            return self.error("expected file name after /include/");
+        }
+
+        let path = self.string()?;
+        let Ok(path) = String::from_utf8(path) else {
+            return self.error("include path is not valid UTF-8");
+        };
+
+        if self.sources.len() > MAX_INCLUDE_DEPTH {
+            return self.error("includes nested too deeply");
+        }
+
+        let from = self.source().name.clone();
+        let Some((name, text)) = (self.loader)(&from, &path) else {
+            return self.error(format!("couldn't open \"{}\"", path));
+        };
+
+        self.sources.push(Source { name, text, pos: 0 });
+        Ok(())
+    }
+
+    fn word(&mut self, allowed: fn(u8) -> bool) -> &str {
+        let n = self.rest().iter().take_while(|&&c| allowed(c)).count();
+        let source = self.source();
+        let start = source.pos;
+        self.bump(n);
+        let source = self.source();
+        &source.text[start..start + n]
+    }
+
+    fn name(&mut self) -> Result<String> {
+        self.skip_ws()?;
+        let name = String::from(self.word(is_name_char));
+        if name.is_empty() {
+            return self.error("expected a node or property name");
+        }
+
+        Ok(name)
+    }
+
+    /// Parses any number of `label:` prefixes
+    fn labels(&mut self) -> Result<Vec<Label>> {
+        let mut labels = Vec::new();
+        loop {
+            self.skip_ws()?;
+            let n = self.rest().iter().take_while(|&&c| is_label_char(c)).count();
+            let is_label = n > 0
+                && !self.rest()[0].is_ascii_digit()
+                && self.peek_at(n) == Some(b':');
+            if !is_label {
+                return Ok(labels);
+            }
+
+            let location = self.location();
+            let name = String::from(self.word(is_label_char));
+            self.bump(1);
+            labels.push(Label { name, location });
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Tree> {
+        let mut tree = Tree::default();
+
+        if !self.eat("/dts-v1/")? {
+            return self.error("missing /dts-v1/ tag");
+        }
+        self.expect(";")?;
+
+        loop {
+            self.skip_ws()?;
+            if self.peek().is_none() {
+                return Ok(tree);
+            }
+
+            if self.eat("/dts-v1/")? {
+                self.expect(";")?;
+                continue;
+            }
+            if self.eat("/plugin/")? {
+                return self.error("overlays (/plugin/) are not supported");
+            }
+
+            let labels = self.labels()?;
+
+            if self.eat("/memreserve/")? {
+                let address = self.integer_prim()?;
+                let size = self.integer_prim()?;
+                self.expect(";")?;
+                tree.mem_rsv.push((address, size));
+            } else if self.eat("/delete-node/")? {
+                let (target, location) = self.reference()?;
+                self.expect(";")?;
+                match tree.root.resolve(&target) {
+                    Some(path) if !path.is_empty() => {
+                        let (last, parent) = path.split_last().unwrap();
+                        tree.root.at_mut(parent).children.remove(*last);
+                    }
+                    _ => return Err(location.error("/delete-node/ of unknown node")),
+                }
+            } else if self.eat("/omit-if-no-ref/")? {
+                let (target, location) = self.reference()?;
+                self.expect(";")?;
+                let Some(path) = tree.root.resolve(&target) else {
+                    return Err(location.error("/omit-if-no-ref/ of unknown node"));
+                };
+                tree.root.at_mut(&path).omit_if_no_ref = true;
+            } else if self.peek() == Some(b'/') {
+                self.bump(1);
+                let def = self.node_def(String::new(), labels, false)?;
+                tree.root.merge(def);
+            } else if self.peek() == Some(b'&') {
+                let (target, location) = self.reference()?;
+                let Some(path) = tree.root.resolve(&target) else {
+                    return Err(location.error("reference to non-existent node or label"));
+                };
+                let def = self.node_def(String::new(), labels, false)?;
+                tree.root.at_mut(&path).merge(def);
+            } else {
+                return self.error("expected a node definition");
+            }
+        }
+    }
+
+    fn reference(&mut self) -> Result<(RefTarget, Location)> {
+        self.skip_ws()?;
+        let location = self.location();
+        if self.peek() != Some(b'&') {
+            return self.error("expected a reference");
+        }
+        self.bump(1);
+
+        if self.peek() == Some(b'{') {
+            let Some(end) = self.rest().iter().position(|&b| b == b'}') else {
+                return self.error("unterminated path reference");
+            };
+            let path = String::from_utf8_lossy(&self.rest()[1..end]).into_owned();
+            self.bump(end + 1);
+            return Ok((RefTarget::Path(path), location));
+        }
+
+        let label = String::from(self.word(is_label_char));
+        if label.is_empty() {
+            return self.error("expected a label after `&`");
+        }
+
+        Ok((RefTarget::Label(label), location))
+    }
+
+    fn node_def(&mut self, name: String, labels: Vec<Label>, omit_if_no_ref: bool) -> Result<NodeDef> {
+        self.expect("{")?;
+
+        let mut items = Vec::new();
+        loop {
+            if self.eat("}")? {
+                break;
+            }
+
+            if self.eat("/delete-property/")? {
+                let name = self.name()?;
+                self.expect(";")?;
+                items.push(Item::DeleteProperty(name));
+                continue;
+            }
+            if self.eat("/delete-node/")? {
+                let name = self.name()?;
+                self.expect(";")?;
+                items.push(Item::DeleteNode(name));
+                continue;
+            }
+
+            let mut labels = Vec::new();
+            let mut omit = false;
+            loop {
+                let more = self.labels()?;
+                let more_omit = self.eat("/omit-if-no-ref/")?;
+                if more.is_empty() && !more_omit {
+                    break;
+                }
+                labels.extend(more);
+                omit |= more_omit;
+            }
+
+            let name = self.name()?;
+            self.skip_ws()?;
+            match self.peek() {
+                Some(b'{') => items.push(Item::Node(self.node_def(name, labels, omit)?)),
+                _ if omit => return self.error("/omit-if-no-ref/ only applies to nodes"),
+                Some(b'=') => {
+                    self.bump(1);
+                    items.push(Item::Property(self.property_value(name)?));
+                }
+                Some(b';') => {
+                    self.bump(1);
+                    items.push(Item::Property(Property { name, value: Vec::new(), refs: Vec::new() }));
+                }
+                _ => return self.error("expected `=`, `;` or `{`"),
+            }
+        }
+
+        self.expect(";")?;
+
+        Ok(NodeDef { name, labels, items, omit_if_no_ref })
+    }
+
+    fn property_value(&mut self, name: String) -> Result<Property> {
+        let mut prop = Property { name, value: Vec::new(), refs: Vec::new() };
+
+        loop {
+            self.labels()?;
+            self.skip_ws()?;
+            match self.peek() {
+                Some(b'"') => {
+                    let s = self.string()?;
+                    prop.value.extend_from_slice(&s);
+                    prop.value.push(0);
+                }
+                Some(b'<') => {
+                    self.bump(1);
+                    self.cells(&mut prop, 32)?;
+                }
+                Some(b'[') => {
+                    self.bump(1);
+                    self.bytes(&mut prop.value)?;
+                }
+                Some(b'&') => {
+                    let (target, location) = self.reference()?;
+                    let offset = prop.value.len();
+                    prop.refs.push(Reference { offset, kind: RefKind::Path, target, location });
+                }
+                Some(b'/') if self.starts_with("/bits/") => {
+                    self.bump("/bits/".len());
+                    let bits = self.integer_prim()?;
+                    if ![8, 16, 32, 64].contains(&bits) {
+                        return self.error("/bits/ must be 8, 16, 32 or 64");
+                    }
+                    self.expect("<")?;
+                    self.cells(&mut prop, bits as u32)?;
+                }
+                Some(b'/') if self.starts_with("/incbin/") => {
+                    return self.error("/incbin/ is not supported");
+                }
+                _ => return self.error("expected a property value"),
+            }
+
+            self.labels()?;
+            if !self.eat(",")? {
+                break;
+            }
+        }
+
+        self.expect(";")?;
+        Ok(prop)
+    }
+
+    /// Parses the inside of `<...>`, the opening bracket already consumed
+    fn cells(&mut self, prop: &mut Property, bits: u32) -> Result<()> {
+        loop {
+            self.labels()?;
+            if self.eat(">")? {
+                return Ok(());
+            }
+
+            if self.peek() == Some(b'&') {
+                if bits != 32 {
+                    return self.error("references are only allowed in arrays with 32-bit elements");
+                }
+                let (target, location) = self.reference()?;
+                let offset = prop.value.len();
+                prop.refs.push(Reference { offset, kind: RefKind::Phandle, target, location });
+                prop.value.extend_from_slice(&u32::MAX.to_be_bytes());
+                continue;
+            }
+
+            let location = self.location();
+            let value = self.integer_prim()?;
+            let mask = if bits == 64 { u64::MAX } else { (1u64 << bits) - 1 };
+            if value > mask && (value | mask) != u64::MAX {
+                return Err(location.error(format!("value out of range for {}-bit array element", bits)));
+            }
+
+            let bytes = (value & mask).to_be_bytes();
+            prop.value.extend_from_slice(&bytes[8 - bits as usize / 8..]);
+        }
+    }
+
+    /// Parses the inside of `[...]`, the opening bracket already consumed
+    fn bytes(&mut self, value: &mut Vec<u8>) -> Result<()> {
+        loop {
+            self.labels()?;
+            if self.eat("]")? {
+                return Ok(());
+            }
+
+            let hex = |c: Option<u8>| c.and_then(|c| (c as char).to_digit(16));
+            match (hex(self.peek()), hex(self.peek_at(1))) {
+                (Some(hi), Some(lo)) => {
+                    value.push((hi << 4 | lo) as u8);
+                    self.bump(2);
+                }
+                _ => return self.error("expected a pair of hex digits"),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<u8> {
+        let Some(c) = self.peek() else {
+            return self.error("unterminated escape sequence");
+        };
+        self.bump(1);
+
+        let byte = match c {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'v' => 0x0b,
+            b'f' => 0x0c,
+            b'r' => b'\r',
+            b'x' => {
+                let n = self.rest().iter().take(2).take_while(|c| c.is_ascii_hexdigit()).count();
+                if n == 0 {
+                    return self.error("expected hex digits after \\x");
+                }
+                let digits = core::str::from_utf8(&self.rest()[..n]).unwrap();
+                let byte = u8::from_str_radix(digits, 16).unwrap();
+                self.bump(n);
+                byte
+            }
+            b'0'..=b'7' => {
+                let n = 1 + self.rest().iter().take(2).take_while(|c| (b'0'..=b'7').contains(c)).count();
+                let source = self.source();
+                let digits = &source.text[source.pos - 1..source.pos - 1 + n];
+                let Ok(byte) = u8::from_str_radix(digits, 8) else {
+                    return self.error("octal escape out of range");
+                };
+                self.bump(n - 1);
+                byte
+            }
+            c => c,
+        };
+
+        Ok(byte)
+    }
+
+    fn string(&mut self) -> Result<Vec<u8>> {
+        self.bump(1);
+
+        let mut s = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(b'\n') => return self.error("unterminated string"),
+                Some(b'"') => {
+                    self.bump(1);
+                    return Ok(s);
+                }
+                Some(b'\\') => {
+                    self.bump(1);
+                    s.push(self.escape()?);
+                }
+                Some(c) => {
+                    self.bump(1);
+                    s.push(c);
+                }
+            }
+        }
+    }
+
+    /// A literal, a character literal or a parenthesized expression
+    fn integer_prim(&mut self) -> Result<u64> {
+        self.skip_ws()?;
+        match self.peek() {
+            Some(b'(') => {
+                self.bump(1);
+                let value = self.expr()?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            Some(b'\'') => {
+                self.bump(1);
+                let value = match self.peek() {
+                    Some(b'\\') => {
+                        self.bump(1);
+                        self.escape()?
+                    }
+                    Some(c) => {
+                        self.bump(1);
+                        c
+                    }
+                    None => return self.error("unterminated character literal"),
+                };
+                if self.peek() != Some(b'\'') {
+                    return self.error("unterminated character literal");
+                }
+                self.bump(1);
+                Ok(value as u64)
+            }
+            Some(c) if c.is_ascii_digit() => self.literal(),
+            _ => self.error("expected an integer"),
+        }
+    }
+
+    fn literal(&mut self) -> Result<u64> {
+        let location = self.location();
+        let word = String::from(self.word(|c| c.is_ascii_alphanumeric()));
+        let digits = word.trim_end_matches(['u', 'U', 'l', 'L']);
+
+        let parsed = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+            u64::from_str_radix(hex, 16)
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            u64::from_str_radix(&digits[1..], 8)
+        } else {
+            digits.parse()
+        };
+
+        parsed.map_err(|_| location.error(format!("invalid integer literal `{}`", word)))
+    }
+
+    fn expr(&mut self) -> Result<u64> {
+        let cond = self.binary(0)?;
+        if self.eat("?")? {
+            let a = self.expr()?;
+            self.expect(":")?;
+            let b = self.expr()?;
+            return Ok(if cond != 0 { a } else { b });
+        }
+
+        Ok(cond)
+    }
+
+    fn binary_op(&self) -> Option<(&'static str, u8)> {
+        const OPS: &[(&str, u8)] = &[
+            ("||", 1), ("&&", 2), ("==", 6), ("!=", 6), ("<=", 7), (">=", 7), ("<<", 8), (">>", 8),
+            ("|", 3), ("^", 4), ("&", 5), ("<", 7), (">", 7), ("+", 9), ("-", 9), ("*", 10),
+            ("/", 10), ("%", 10),
+        ];
+
+        OPS.iter().copied().find(|(op, _)| self.starts_with(op))
+    }
+
+    fn binary(&mut self, min_prec: u8) -> Result<u64> {
+        let mut lhs = self.unary()?;
+        loop {
+            self.skip_ws()?;
+            let Some((op, prec)) = self.binary_op().filter(|&(_, prec)| prec >= min_prec) else {
+                return Ok(lhs);
+            };
+
+            let location = self.location();
+            self.bump(op.len());
+            let rhs = self.binary(prec + 1)?;
+            lhs = match op {
+                "||" => (lhs != 0 || rhs != 0) as u64,
+                "&&" => (lhs != 0 && rhs != 0) as u64,
+                "|" => lhs | rhs,
+                "^" => lhs ^ rhs,
+                "&" => lhs & rhs,
+                "==" => (lhs == rhs) as u64,
+                "!=" => (lhs != rhs) as u64,
+                "<" => (lhs < rhs) as u64,
+                ">" => (lhs > rhs) as u64,
+                "<=" => (lhs <= rhs) as u64,
+                ">=" => (lhs >= rhs) as u64,
+                "<<" => lhs.checked_shl(rhs as u32).unwrap_or(0),
+                ">>" => lhs.checked_shr(rhs as u32).unwrap_or(0),
+                "+" => lhs.wrapping_add(rhs),
+                "-" => lhs.wrapping_sub(rhs),
+                "*" => lhs.wrapping_mul(rhs),
+                "/" | "%" if rhs == 0 => return Err(location.error("division by zero")),
+                "/" => lhs / rhs,
+                "%" => lhs % rhs,
+                _ => unreachable!(),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<u64> {
+        if self.eat("-")? {
+            return Ok(self.unary()?.wrapping_neg());
+        }
+        if self.eat("~")? {
+            return Ok(!self.unary()?);
+        }
+        if self.eat("!")? {
+            return Ok((self.unary()? == 0) as u64);
+        }
+
+        self.integer_prim()
+    }
+}
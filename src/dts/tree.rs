@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! In-memory devicetree built while parsing DTS source
+
+use alloc::{string::String, vec::Vec};
+
+use super::DtsError;
+
+/// A position in the source, used for diagnostics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn error(&self, message: impl Into<String>) -> DtsError {
+        DtsError {
+            file: self.file.clone(),
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+}
+
+/// The target of a `&label` or `&{/path}` reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum RefTarget {
+    Label(String),
+    Path(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RefKind {
+    /// A cell inside `<...>` replaced by the phandle of the target
+    Phandle,
+    /// The full path of the target inserted as a string
+    Path,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Reference {
+    pub offset: usize,
+    pub kind: RefKind,
+    pub target: RefTarget,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Property {
+    pub name: String,
+    pub value: Vec<u8>,
+    pub refs: Vec<Reference>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Label {
+    pub name: String,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Node {
+    pub name: String,
+    pub labels: Vec<Label>,
+    pub props: Vec<Property>,
+    pub children: Vec<Node>,
+    pub omit_if_no_ref: bool,
+    pub referenced: bool,
+}
+
+/// One `{ ... };` block, applied in order onto the tree
+#[derive(Debug, Clone, Default)]
+pub(super) struct NodeDef {
+    pub name: String,
+    pub labels: Vec<Label>,
+    pub items: Vec<Item>,
+    pub omit_if_no_ref: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Item {
+    Property(Property),
+    DeleteProperty(String),
+    Node(NodeDef),
+    DeleteNode(String),
+}
+
+/// Index path from the root to a node
+pub(super) type NodePath = Vec<usize>;
+
+impl Node {
+    fn new(name: String) -> Self {
+        Self { name, ..Default::default() }
+    }
+
+    /// Applies the items of `def` on top of the node, like a later definition
+    /// of the same node in the source does
+    pub fn merge(&mut self, def: NodeDef) {
+        for label in def.labels {
+            if !self.labels.iter().any(|l| l.name == label.name) {
+                self.labels.push(label);
+            }
+        }
+        self.omit_if_no_ref |= def.omit_if_no_ref;
+
+        for item in def.items {
+            match item {
+                Item::Property(prop) => match self.props.iter_mut().find(|p| p.name == prop.name) {
+                    Some(existing) => *existing = prop,
+                    None => self.props.push(prop),
+                },
+                Item::DeleteProperty(name) => self.props.retain(|p| p.name != name),
+                Item::Node(child) => {
+                    match self.children.iter_mut().find(|c| c.name == child.name) {
+                        Some(existing) => existing.merge(child),
+                        None => {
+                            let mut node = Node::new(child.name.clone());
+                            node.merge(child);
+                            self.children.push(node);
+                        }
+                    }
+                }
+                Item::DeleteNode(name) => self.children.retain(|c| c.name != name),
+            }
+        }
+    }
+
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.props.iter().find(|p| p.name == name)
+    }
+
+    pub fn at(&self, path: &[usize]) -> &Node {
+        path.iter().fold(self, |node, &i| &node.children[i])
+    }
+
+    pub fn at_mut(&mut self, path: &[usize]) -> &mut Node {
+        path.iter().fold(self, |node, &i| &mut node.children[i])
+    }
+
+    /// Full path of the node at `path` below `self`, which must be the root
+    pub fn full_path(&self, path: &[usize]) -> String {
+        if path.is_empty() {
+            return String::from("/");
+        }
+
+        let mut node = self;
+        let mut full = String::new();
+        for &i in path {
+            node = &node.children[i];
+            full.push('/');
+            full.push_str(&node.name);
+        }
+
+        full
+    }
+
+    /// Looks up a node by absolute path; a component without a unit address
+    /// matches the first node with that base name
+    pub fn find_path(&self, path: &str) -> Option<NodePath> {
+        let mut node = self;
+        let mut indices = Vec::new();
+        for part in path.split('/').filter(|p| !p.is_empty()) {
+            let i = node.children.iter().position(|c| c.name == part).or_else(|| {
+                if part.contains('@') {
+                    return None;
+                }
+                node.children.iter().position(|c| c.name.split('@').next() == Some(part))
+            })?;
+            indices.push(i);
+            node = &node.children[i];
+        }
+
+        Some(indices)
+    }
+
+    pub fn find_label(&self, label: &str) -> Option<NodePath> {
+        if self.labels.iter().any(|l| l.name == label) {
+            return Some(Vec::new());
+        }
+
+        self.children.iter().enumerate().find_map(|(i, child)| {
+            let mut path = child.find_label(label)?;
+            path.insert(0, i);
+            Some(path)
+        })
+    }
+
+    pub fn resolve(&self, target: &RefTarget) -> Option<NodePath> {
+        match target {
+            RefTarget::Label(label) => self.find_label(label),
+            RefTarget::Path(path) => self.find_path(path),
+        }
+    }
+
+    /// Calls `f` with the index path of every node, parents first
+    pub fn walk(&self, f: &mut impl FnMut(&Node, &[usize])) {
+        fn inner(node: &Node, path: &mut NodePath, f: &mut impl FnMut(&Node, &[usize])) {
+            f(node, path);
+            for (i, child) in node.children.iter().enumerate() {
+                path.push(i);
+                inner(child, path, f);
+                path.pop();
+            }
+        }
+
+        inner(self, &mut Vec::new(), f)
+    }
+
+    /// Returns the child named `name`, creating it if needed
+    pub fn child_mut(&mut self, name: &str) -> &mut Node {
+        let i = match self.children.iter().position(|c| c.name == name) {
+            Some(i) => i,
+            None => {
+                self.children.push(Node::new(String::from(name)));
+                self.children.len() - 1
+            }
+        };
+
+        &mut self.children[i]
+    }
+
+    /// Removes every node flagged with `/omit-if-no-ref/` that nothing refers to
+    pub fn remove_unreferenced(&mut self) {
+        self.children.retain(|c| !c.omit_if_no_ref || c.referenced);
+        for child in &mut self.children {
+            child.remove_unreferenced();
+        }
+    }
+}
+
+/// A parsed source file
+#[derive(Debug, Clone, Default)]
+pub(super) struct Tree {
+    pub mem_rsv: Vec<(u64, u64)>,
+    pub root: Node,
+}
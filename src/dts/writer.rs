@@ -0,0 +1,331 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! DTS emitter
+//!
+//! Property values are classified the same way `dtc -O dts` does it, so the
+//! emitted source compiles back to an equivalent blob.
+
+use core::fmt::{self, Write};
+
+use crate::{
+    node::{self, FdtNode, NodeProperty, MAX_DEPTH},
+    parsing::FdtData,
+    LinuxFdt,
+};
+
+/// Properties that always hold cells, even if their bytes happen to look
+/// like a string
+const CELL_PROPERTIES: &[&str] = &["reg", "ranges", "dma-ranges", "phandle", "linux,phandle"];
+
+/// Properties that always hold a single phandle
+const PHANDLE_PROPERTIES: &[&str] = &["interrupt-parent"];
+
+/// Indentation used for each nesting level of the emitted source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    /// One tab per level, like `dtc -O dts`
+    Tabs,
+    /// The given number of spaces per level
+    Spaces(usize),
+}
+
+/// Writes a devicetree back out as `dtc`-compatible source
+///
+/// Cell arrays are emitted as `<0x..>`, byte strings as `[..]` and string
+/// lists as `"a", "b"`. Labels are recovered from `/__symbols__`, and the
+/// phandle cells recorded in `/__local_fixups__` (plus `interrupt-parent`)
+/// are written as `&label` references, or `&{/path}` when the target has no
+/// label.
+#[derive(Debug, Clone, Copy)]
+pub struct DtsWriter {
+    indent: Indent,
+}
+
+impl Default for DtsWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DtsWriter {
+    /// Creates a writer indenting with tabs
+    pub fn new() -> Self {
+        Self { indent: Indent::Tabs }
+    }
+
+    /// Sets the indentation used for each nesting level
+    pub fn indent(mut self, indent: Indent) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Writes the `/dts-v1/;` header, the `/memreserve/` entries and the whole
+    /// node tree of `fdt`
+    pub fn write<W: Write>(&self, fdt: &LinuxFdt<'_>, out: &mut W) -> fmt::Result {
+        writeln!(out, "/dts-v1/;")?;
+        writeln!(out)?;
+
+        for rsv in fdt.sys_memory_reservations() {
+            writeln!(out, "/memreserve/ {:#018x} {:#018x};", rsv.start(), rsv.size())?;
+        }
+
+        let root = fdt.root().node;
+        let ctx = Context {
+            writer: self,
+            fdt,
+            root,
+            symbols: fdt.find_node("/__symbols__"),
+        };
+
+        let mut path = [""; MAX_DEPTH];
+        ctx.write_node(out, root, &mut path, 0, fdt.find_node("/__local_fixups__"))
+    }
+}
+
+struct Context<'w, 'b, 'a> {
+    writer: &'w DtsWriter,
+    fdt: &'b LinuxFdt<'a>,
+    root: FdtNode<'b, 'a>,
+    symbols: Option<FdtNode<'b, 'a>>,
+}
+
+impl<'b, 'a: 'b> Context<'_, 'b, 'a> {
+    fn write_indent<W: Write>(&self, out: &mut W, depth: usize) -> fmt::Result {
+        for _ in 0..depth {
+            match self.writer.indent {
+                Indent::Tabs => out.write_char('\t')?,
+                Indent::Spaces(n) => write!(out, "{:n$}", "")?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `path` holds the names of the ancestors of `node`, root excluded
+    fn write_node<W: Write>(
+        &self,
+        out: &mut W,
+        node: FdtNode<'b, 'a>,
+        path: &mut [&'a str; MAX_DEPTH],
+        depth: usize,
+        fixups: Option<FdtNode<'b, 'a>>,
+    ) -> fmt::Result {
+        self.write_indent(out, depth)?;
+        self.write_labels(out, path[..depth].iter().copied())?;
+        writeln!(out, "{} {{", if depth == 0 { "/" } else { node.name })?;
+
+        for prop in node.properties() {
+            self.write_indent(out, depth + 1)?;
+            self.write_property(out, prop, fixups.and_then(|f| f.property(prop.name)))?;
+        }
+
+        for child in node.children() {
+            if depth + 1 >= MAX_DEPTH {
+                return Err(fmt::Error);
+            }
+
+            path[depth] = child.name;
+            let child_fixups = fixups.and_then(|f| f.children().find(|c| c.name == child.name));
+
+            writeln!(out)?;
+            self.write_node(out, child, path, depth + 1, child_fixups)?;
+        }
+
+        self.write_indent(out, depth)?;
+        writeln!(out, "}};")
+    }
+
+    fn write_labels<'p, W: Write>(
+        &self,
+        out: &mut W,
+        path: impl Iterator<Item = &'p str> + Clone,
+    ) -> fmt::Result {
+        for label in self.labels(path) {
+            write!(out, "{}: ", label)?;
+        }
+
+        Ok(())
+    }
+
+    fn labels<'p>(
+        &self,
+        path: impl Iterator<Item = &'p str> + Clone,
+    ) -> impl Iterator<Item = &'a str> {
+        self.symbols
+            .into_iter()
+            .flat_map(|symbols| symbols.properties())
+            .filter(move |p| p.as_str().is_some_and(|value| path_is(value, path.clone())))
+            .map(|p| p.name)
+    }
+
+    fn write_property<W: Write>(
+        &self,
+        out: &mut W,
+        prop: NodeProperty<'a>,
+        fixup: Option<NodeProperty<'a>>,
+    ) -> fmt::Result {
+        if prop.value.is_empty() {
+            return writeln!(out, "{};", prop.name);
+        }
+
+        write!(out, "{} = ", prop.name)?;
+
+        let has_refs = fixup.is_some() || PHANDLE_PROPERTIES.contains(&prop.name);
+        let cells = has_refs || CELL_PROPERTIES.contains(&prop.name) || prop.name.ends_with("-cells");
+
+        match ValueKind::guess(prop.value, cells) {
+            ValueKind::Strings => write_strings(out, prop.value)?,
+            ValueKind::Cells => self.write_cells(out, prop, fixup)?,
+            ValueKind::Bytes => write_bytes(out, prop.value)?,
+        }
+
+        writeln!(out, ";")
+    }
+
+    fn write_cells<W: Write>(
+        &self,
+        out: &mut W,
+        prop: NodeProperty<'a>,
+        fixup: Option<NodeProperty<'a>>,
+    ) -> fmt::Result {
+        let is_ref = |offset: u32| match fixup {
+            Some(fixup) => {
+                let mut offsets = FdtData::new(fixup.value);
+                core::iter::from_fn(|| offsets.u32()).any(|o| o.get() == offset)
+            }
+            None => PHANDLE_PROPERTIES.contains(&prop.name) && offset == 0,
+        };
+
+        out.write_char('<')?;
+
+        let mut stream = FdtData::new(prop.value);
+        let mut offset = 0;
+        while let Some(cell) = stream.u32() {
+            if offset > 0 {
+                out.write_char(' ')?;
+            }
+
+            let target = if is_ref(offset) { self.fdt.find_phandle(cell.get()) } else { None };
+            match target {
+                Some(target) => self.write_reference(out, target, cell.get())?,
+                None => write!(out, "{:#04x}", cell.get())?,
+            }
+
+            offset += 4;
+        }
+
+        out.write_char('>')
+    }
+
+    fn write_reference<W: Write>(
+        &self,
+        out: &mut W,
+        target: FdtNode<'b, 'a>,
+        phandle: u32,
+    ) -> fmt::Result {
+        let mut stack = [self.root; MAX_DEPTH];
+        let Some(depth) = node::ancestry(self.root, target, &mut stack) else {
+            return write!(out, "{:#04x}", phandle);
+        };
+
+        let path = stack[1..depth].iter().map(|n| n.name);
+        if let Some(label) = self.labels(path.clone()).next() {
+            return write!(out, "&{}", label);
+        }
+
+        out.write_str("&{")?;
+        if depth == 1 {
+            out.write_char('/')?;
+        }
+        for name in path {
+            write!(out, "/{}", name)?;
+        }
+        out.write_char('}')
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Strings,
+    Cells,
+    Bytes,
+}
+
+impl ValueKind {
+    /// Close to the `dtc` heuristic: NUL terminated printable text is a
+    /// string list (unless it contains empty strings, which small cell values
+    /// such as `<0x384000>` tend to look like), anything 4-byte sized is a
+    /// cell array and the rest is a byte string
+    fn guess(value: &[u8], cells: bool) -> Self {
+        let stringy = value == [0]
+            || (value.last() == Some(&0)
+                && value.iter().all(|&b| is_string_byte(b))
+                && value[..value.len() - 1].split(|&b| b == 0).all(|s| !s.is_empty()));
+
+        if stringy && !(cells && value.len().is_multiple_of(4)) {
+            ValueKind::Strings
+        } else if value.len().is_multiple_of(4) {
+            ValueKind::Cells
+        } else {
+            ValueKind::Bytes
+        }
+    }
+}
+
+fn is_string_byte(b: u8) -> bool {
+    b == 0 || b == b' ' || b.is_ascii_graphic() || b"\x07\x08\t\n\x0b\x0c\r".contains(&b)
+}
+
+fn write_strings<W: Write>(out: &mut W, value: &[u8]) -> fmt::Result {
+    for (i, s) in value[..value.len() - 1].split(|&b| b == 0).enumerate() {
+        if i > 0 {
+            out.write_str(", ")?;
+        }
+
+        out.write_char('"')?;
+        for &b in s {
+            match b {
+                0x07 => out.write_str("\\a")?,
+                0x08 => out.write_str("\\b")?,
+                b'\t' => out.write_str("\\t")?,
+                b'\n' => out.write_str("\\n")?,
+                0x0b => out.write_str("\\v")?,
+                0x0c => out.write_str("\\f")?,
+                b'\r' => out.write_str("\\r")?,
+                b'\\' => out.write_str("\\\\")?,
+                b'"' => out.write_str("\\\"")?,
+                b if b == b' ' || b.is_ascii_graphic() => out.write_char(b as char)?,
+                b => write!(out, "\\x{:02x}", b)?,
+            }
+        }
+        out.write_char('"')?;
+    }
+
+    Ok(())
+}
+
+fn write_bytes<W: Write>(out: &mut W, value: &[u8]) -> fmt::Result {
+    out.write_char('[')?;
+    for (i, b) in value.iter().enumerate() {
+        if i > 0 {
+            out.write_char(' ')?;
+        }
+        write!(out, "{:02x}", b)?;
+    }
+    out.write_char(']')
+}
+
+/// Whether the absolute `path` names the node reached through `names`
+fn path_is<'p>(path: &str, mut names: impl Iterator<Item = &'p str>) -> bool {
+    let mut parts = path.split('/').filter(|s| !s.is_empty());
+    loop {
+        match (parts.next(), names.next()) {
+            (None, None) => return true,
+            (Some(a), Some(b)) if a == b => {}
+            _ => return false,
+        }
+    }
+}
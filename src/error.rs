@@ -15,6 +15,21 @@ pub enum FdtError {
     /// The slice passed in was too small to fit the given total size of the FDT
     /// structure
     BufferTooSmall,
+    /// A blob was built out of order, e.g. a property was added after a
+    /// subnode or nodes were left open
+    BadState,
+    /// The FDT version is too old to be modified in place
+    BadVersion,
+    /// The blocks of the FDT are not laid out in the standard order
+    /// (memory reservations, structure, strings)
+    BadLayout,
+    /// The buffer has no room left, either to grow the FDT or to hold more
+    /// entries
+    NoSpace,
+    /// The requested entry does not exist
+    NotFound,
+    /// An argument is invalid, e.g. a zero sized memory reservation
+    BadValue,
 }
 
 impl core::fmt::Display for FdtError {
@@ -25,6 +40,56 @@ impl core::fmt::Display for FdtError {
             FdtError::BufferTooSmall => {
                 write!(f, "the given buffer was too small to contain a FDT header")
             }
+            FdtError::BadState => write!(f, "the FDT was built in an invalid order"),
+            FdtError::BadVersion => write!(f, "the FDT version is not supported"),
+            FdtError::BadLayout => write!(f, "the FDT blocks are not in the standard order"),
+            FdtError::NoSpace => write!(f, "not enough space left in the buffer"),
+            FdtError::NotFound => write!(f, "the requested entry was not found"),
+            FdtError::BadValue => write!(f, "an invalid value was passed"),
+        }
+    }
+}
+
+/// Reasons a `/reserved-memory` region can't be set up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservedMemoryError {
+    /// `/reserved-memory` lacks `#address-cells` or `#size-cells`
+    MissingCells,
+    /// `/reserved-memory` lacks `ranges`
+    MissingRanges,
+    /// `/reserved-memory` cell sizes differ from the root node's
+    CellsMismatch,
+    /// The region has neither `reg` nor `size`
+    MissingRegOrSize,
+    /// `reg` doesn't fit the cell sizes
+    BadReg,
+    /// The region has a zero or unreadable `size`
+    BadSize,
+    /// The `alignment` is unreadable or not a power of two
+    BadAlignment,
+    /// `alloc-ranges` doesn't fit the cell sizes
+    BadAllocRanges,
+    /// No free memory satisfies the size, alignment and `alloc-ranges`
+    NoMemory,
+    /// The memory map has no room left to record the region
+    NoSpace,
+}
+
+impl core::fmt::Display for ReservedMemoryError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReservedMemoryError::MissingCells => write!(f, "/reserved-memory lacks #address-cells or #size-cells"),
+            ReservedMemoryError::MissingRanges => write!(f, "/reserved-memory lacks ranges"),
+            ReservedMemoryError::CellsMismatch => {
+                write!(f, "/reserved-memory cell sizes differ from the root node")
+            }
+            ReservedMemoryError::MissingRegOrSize => write!(f, "reserved memory node has neither reg nor size"),
+            ReservedMemoryError::BadReg => write!(f, "invalid reserved memory reg"),
+            ReservedMemoryError::BadSize => write!(f, "invalid reserved memory size"),
+            ReservedMemoryError::BadAlignment => write!(f, "invalid reserved memory alignment"),
+            ReservedMemoryError::BadAllocRanges => write!(f, "invalid reserved memory alloc-ranges"),
+            ReservedMemoryError::NoMemory => write!(f, "no free memory for the reserved memory region"),
+            ReservedMemoryError::NoSpace => write!(f, "the memory map is full"),
         }
     }
 }
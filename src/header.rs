@@ -7,6 +7,17 @@
 
 use crate::parsing::{BigEndianU32, FdtData};
 
+/// FDT header magic value
+pub(crate) const FDT_MAGIC: u32 = 0xd00dfeed;
+/// Size in bytes of the version 17 header
+pub(crate) const FDT_HEADER_SIZE: usize = 40;
+/// Version of the blobs produced by this crate
+#[cfg(feature = "alloc")]
+pub(crate) const FDT_VERSION: u32 = 17;
+/// Oldest version the blobs produced by this crate are compatible with
+#[cfg(feature = "alloc")]
+pub(crate) const FDT_LAST_COMP_VERSION: u32 = 16;
+
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
 pub(crate) struct FdtHeader {
@@ -15,14 +26,14 @@ pub(crate) struct FdtHeader {
     /// Total size in bytes of the FDT structure
     pub(crate) totalsize: BigEndianU32,
     /// Offset in bytes from the start of the header to the structure block
-    off_dt_struct: BigEndianU32,
+    pub(crate) off_dt_struct: BigEndianU32,
     /// Offset in bytes from the start of the header to the strings block
-    off_dt_strings: BigEndianU32,
+    pub(crate) off_dt_strings: BigEndianU32,
     /// Offset in bytes from the start of the header to the memory reservation
     /// block
     pub(crate) off_mem_rsvmap: BigEndianU32,
     /// FDT version
-    version: BigEndianU32,
+    pub(crate) version: BigEndianU32,
     /// Last compatible FDT version
     last_comp_version: BigEndianU32,
     /// System boot CPU ID
@@ -35,7 +46,7 @@ pub(crate) struct FdtHeader {
 
 impl FdtHeader {
     pub(crate) fn valid_magic(&self) -> bool {
-        self.magic.get() == 0xd00dfeed
+        self.magic.get() == FDT_MAGIC
     }
 
     pub(crate) fn struct_range(&self) -> core::ops::Range<usize> {
@@ -52,6 +63,26 @@ impl FdtHeader {
         start..end
     }
 
+    /// Writes the header back into the first [`FDT_HEADER_SIZE`] bytes of `bytes`
+    pub(crate) fn store(&self, bytes: &mut [u8]) {
+        let fields = [
+            self.magic,
+            self.totalsize,
+            self.off_dt_struct,
+            self.off_dt_strings,
+            self.off_mem_rsvmap,
+            self.version,
+            self.last_comp_version,
+            self.boot_cpuid_phys,
+            self.size_dt_strings,
+            self.size_dt_struct,
+        ];
+
+        for (chunk, field) in bytes[..FDT_HEADER_SIZE].chunks_exact_mut(4).zip(fields) {
+            chunk.copy_from_slice(&field.get().to_be_bytes());
+        }
+    }
+
     pub(crate) fn from_bytes(bytes: &mut FdtData<'_>) -> Option<Self> {
         Some(Self {
             magic: bytes.u32()?,
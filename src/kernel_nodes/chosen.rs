@@ -5,7 +5,13 @@
 
 //! Linux kernel chosen nodes
 
+use core::ops::Range;
+
+use crate::framebuffer::Framebuffer;
 use crate::node::FdtNode;
+use crate::parsing::{BigEndianU32, BigEndianU64};
+use crate::phandle::property_parts;
+use crate::serial::{ConsoleSpec, EarlyConsole, Uart};
 use crate::standard_nodes::RegIter;
 
 /// Represents the `/chosen` node with specific helper methods
@@ -17,6 +23,52 @@ pub struct Stdout<'b, 'a> {
     pub options: Option<&'a str>,
 }
 
+impl<'b, 'a: 'b> Stdout<'b, 'a> {
+    /// Line settings parsed from the options
+    pub fn spec(&self) -> Option<ConsoleSpec> {
+        ConsoleSpec::parse(self.options?)
+    }
+
+    /// The node as a serial controller
+    pub fn uart(&self) -> Uart<'b, 'a> {
+        Uart::new(self.node)
+    }
+}
+
+/// Node named by `stdin-path`, with the options after the `:`
+pub type Stdin<'b, 'a> = Stdout<'b, 'a>;
+
+/// UEFI system table and memory map handed over by the EFI stub
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UefiParams {
+    /// `linux,uefi-system-table`: physical address of the system table
+    pub system_table: u64,
+    /// `linux,uefi-mmap-start`: physical address of the memory map
+    pub mmap_start: u64,
+    /// `linux,uefi-mmap-size`: size of the memory map in bytes
+    pub mmap_size: u32,
+    /// `linux,uefi-mmap-desc-size`: size of one memory map descriptor
+    pub mmap_desc_size: u32,
+    /// `linux,uefi-mmap-desc-ver`: memory map descriptor version
+    pub mmap_desc_version: u32,
+}
+
+impl UefiParams {
+    /// Reads the `<prefix>,uefi-*` properties of `node`, `linux` for
+    /// `/chosen` and `xen` for `/hypervisor/uefi`
+    pub(crate) fn from_node(node: FdtNode<'_, '_>, prefix: &str) -> Option<Self> {
+        let number = |name: &str| number(property_parts(node, &[prefix, ",uefi-", name])?.value);
+
+        Some(UefiParams {
+            system_table: number("system-table")?,
+            mmap_start: number("mmap-start")?,
+            mmap_size: number("mmap-size")?.try_into().ok()?,
+            mmap_desc_size: number("mmap-desc-size")?.try_into().ok()?,
+            mmap_desc_version: number("mmap-desc-ver")?.try_into().ok()?,
+        })
+    }
+}
+
 /// Represents the `/chosen` node with specific helper methods
 #[derive(Debug, Clone, Copy)]
 pub struct Chosen<'b, 'a> {
@@ -49,16 +101,67 @@ impl<'b, 'a: 'b> Chosen<'b, 'a> {
             }
         }
 
-        let stdout_path = stdout_path.unwrap();
-        let stdout_path = core::str::from_utf8(&stdout_path.value[..stdout_path.value.len() - 1]).ok()?;
-        let (node_name, options) = stdout_path.split_once(':').unwrap_or((stdout_path, ""));
-        let node = self.node.header.find_node(node_name)?;
+        self.resolve_path(stdout_path?.as_str()?)
+    }
 
-        if options.is_empty() {
-            return Some(Stdout { node, options: None });
-        } else {
-            return Some(Stdout { node, options: Some(options) });
-        }
+    /// Early console on the `stdout-path` UART, if it is a known controller
+    pub fn early_console(self) -> Option<EarlyConsole> {
+        let stdout = self.stdout()?;
+        stdout.uart().early_console(stdout.spec())
+    }
+
+    /// Available `simple-framebuffer` nodes under `/chosen`, set up by
+    /// firmware
+    pub fn framebuffers(self) -> impl Iterator<Item = Framebuffer<'b, 'a>> + 'b {
+        self.node.children().filter(|node| node.is_available()).filter_map(Framebuffer::new)
+    }
+
+    /// Searches for the node named by `stdin-path`, like [`Chosen::stdout`]
+    pub fn stdin(self) -> Option<Stdin<'b, 'a>> {
+        self.resolve_path(self.node.property("stdin-path")?.as_str()?)
+    }
+
+    /// `linux,initrd-start` and `linux,initrd-end`, each either 32 or 64-bit
+    pub fn initrd(self) -> Option<Range<u64>> {
+        let start = self.number("linux,initrd-start")?;
+        let end = self.number("linux,initrd-end")?;
+        (start <= end).then_some(start..end)
+    }
+
+    /// `kaslr-seed`: 64-bit seed for kernel address randomization
+    pub fn kaslr_seed(self) -> Option<u64> {
+        BigEndianU64::from_bytes(self.node.property("kaslr-seed")?.value).map(|v| v.get())
+    }
+
+    /// `rng-seed`: entropy for the kernel's random number generator
+    pub fn rng_seed(self) -> Option<&'a [u8]> {
+        self.node.property("rng-seed").map(|p| p.value)
+    }
+
+    /// `linux,elfcorehdr`: the ELF core header of the crashed kernel, for
+    /// a kdump kernel
+    pub fn elfcorehdr(self) -> Option<Range<u64>> {
+        self.address_range("linux,elfcorehdr")
+    }
+
+    /// `linux,booted-from-kexec`: the kernel was started by kexec
+    pub fn booted_from_kexec(self) -> bool {
+        self.node.property("linux,booted-from-kexec").is_some()
+    }
+
+    /// `linux,uefi-*` properties set by the EFI stub, if all are present
+    pub fn uefi(self) -> Option<UefiParams> {
+        UefiParams::from_node(self.node, "linux")
+    }
+
+    /// `linux,ima-kexec-buffer`: IMA measurement list carried over kexec
+    pub fn ima_kexec_buffer(self) -> Option<Range<u64>> {
+        self.address_range("linux,ima-kexec-buffer")
+    }
+
+    /// `linux,tpm-kexec-buffer`: TPM event log carried over kexec
+    pub fn tpm_kexec_buffer(self) -> Option<Range<u64>> {
+        self.address_range("linux,tpm-kexec-buffer")
     }
 
     /// `linux,usable-memory-range` property
@@ -77,4 +180,34 @@ impl<'b, 'a: 'b> Chosen<'b, 'a> {
         }
         None
     }
+
+    fn resolve_path(self, path: &'a str) -> Option<Stdout<'b, 'a>> {
+        let (node_name, options) = path.split_once(':').unwrap_or((path, ""));
+        let node = self.node.header.find_node(node_name)?;
+
+        if options.is_empty() {
+            Some(Stdout { node, options: None })
+        } else {
+            Some(Stdout { node, options: Some(options) })
+        }
+    }
+
+    fn number(self, name: &str) -> Option<u64> {
+        number(self.node.property(name)?.value)
+    }
+
+    /// Reads an address and size pair sized by the root cell sizes
+    fn address_range(self, name: &str) -> Option<Range<u64>> {
+        let (start, size) = self.node.property(name)?.as_reg(self.node.parent_cell_sizes())?.next_raw()?;
+        Some(start..start.checked_add(size)?)
+    }
+}
+
+/// Reads a 32 or 64-bit number, whichever the property holds
+fn number(value: &[u8]) -> Option<u64> {
+    match value.len() {
+        4 => BigEndianU32::from_bytes(value).map(|v| v.get() as u64),
+        8 => BigEndianU64::from_bytes(value).map(|v| v.get()),
+        _ => None,
+    }
 }
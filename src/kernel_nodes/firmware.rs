@@ -0,0 +1,296 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Firmware interface nodes under `/firmware`, and the SCPI node that
+//! usually sits at the root
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/firmware/arm,scmi.yaml,
+//! https://www.kernel.org/doc/Documentation/devicetree/bindings/firmware/arm,scpi.yaml,
+//! https://www.kernel.org/doc/Documentation/devicetree/bindings/arm/firmware/linaro,optee-tz.yaml
+//! and https://www.kernel.org/doc/Documentation/devicetree/bindings/arm/firmware/sdei.txt
+
+use crate::kernel_nodes::psci::{method, PsciMethod};
+use crate::node::FdtNode;
+use crate::parsing::BigEndianU32;
+use crate::phandle::PhandleArgs;
+
+/// Represents the `/firmware` node with specific helper methods
+///
+/// PSCI, the other TF-A service with a node, is found through
+/// [`crate::LinuxFdt::psci`], and SCPI through [`crate::LinuxFdt::scpi`].
+#[derive(Debug, Clone, Copy)]
+pub struct Firmware<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Firmware<'b, 'a> {
+    /// The `/firmware` node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// OP-TEE, `linaro,optee-tz`
+    pub fn optee(&self) -> Option<OpTee<'b, 'a>> {
+        self.find(&["linaro,optee-tz"]).map(|node| OpTee { node })
+    }
+
+    /// SCMI agent, whatever its transport
+    pub fn scmi(&self) -> Option<Scmi<'b, 'a>> {
+        self.find(&["arm,scmi", "arm,scmi-smc", "arm,scmi-smc-param", "linaro,scmi-optee", "arm,scmi-virtio"])
+            .map(|node| Scmi { node })
+    }
+
+    /// TF-A Software Delegated Exception Interface, `arm,sdei-1.0`
+    pub fn sdei(&self) -> Option<Sdei<'b, 'a>> {
+        self.find(&["arm,sdei-1.0"]).map(|node| Sdei { node })
+    }
+
+    fn find(&self, compatibles: &[&str]) -> Option<FdtNode<'b, 'a>> {
+        self.node
+            .children()
+            .filter(|child| child.is_available())
+            .find(|child| child.compatible().is_some_and(|c| c.all().any(|c| compatibles.contains(&c))))
+    }
+}
+
+/// Represents the OP-TEE node
+#[derive(Debug, Clone, Copy)]
+pub struct OpTee<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> OpTee<'b, 'a> {
+    /// The OP-TEE node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// `method` used to call into the secure world
+    pub fn method(&self) -> Option<PsciMethod> {
+        method(self.node)
+    }
+
+    /// Cells of the asynchronous notification interrupt, empty when the
+    /// node has none
+    pub fn interrupt(&self) -> impl Iterator<Item = u32> + 'a {
+        let cells = self.node.interrupt_domain_cells().unwrap_or(0);
+        let value = self.node.property("interrupts").map(|p| p.value).unwrap_or(&[]);
+        let spec = value.get(..cells * 4).unwrap_or(&[]);
+        spec.chunks_exact(4).filter_map(|cell| BigEndianU32::from_bytes(cell).map(|v| v.get()))
+    }
+}
+
+/// How the SCMI agent talks to the platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScmiTransport {
+    /// `arm,scmi`: doorbells through `mboxes`
+    Mailbox,
+    /// `arm,scmi-smc`/`arm,scmi-smc-param`: an SMC with function ID
+    /// `arm,smc-id`
+    Smc {
+        /// `arm,smc-id`
+        smc_id: Option<u32>,
+    },
+    /// `linaro,scmi-optee`: an OP-TEE service
+    Optee,
+    /// `arm,scmi-virtio`: virtio queues
+    Virtio,
+}
+
+/// SCMI protocols with a standard ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScmiProtocolKind {
+    /// `0x10`
+    Base,
+    /// `0x11`
+    PowerDomain,
+    /// `0x12`
+    System,
+    /// `0x13`
+    Performance,
+    /// `0x14`
+    Clock,
+    /// `0x15`
+    Sensor,
+    /// `0x16`
+    Reset,
+    /// `0x17`
+    Voltage,
+    /// `0x18`
+    Powercap,
+    /// `0x19`
+    Pinctrl,
+}
+
+/// A protocol child of the SCMI node
+#[derive(Debug, Clone, Copy)]
+pub struct ScmiProtocol<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+    pub(crate) id: u32,
+}
+
+impl<'b, 'a: 'b> ScmiProtocol<'b, 'a> {
+    /// The protocol node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// Protocol ID from `reg`
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Standard protocol the ID names, `None` for vendor protocols
+    pub fn kind(&self) -> Option<ScmiProtocolKind> {
+        Some(match self.id {
+            0x10 => ScmiProtocolKind::Base,
+            0x11 => ScmiProtocolKind::PowerDomain,
+            0x12 => ScmiProtocolKind::System,
+            0x13 => ScmiProtocolKind::Performance,
+            0x14 => ScmiProtocolKind::Clock,
+            0x15 => ScmiProtocolKind::Sensor,
+            0x16 => ScmiProtocolKind::Reset,
+            0x17 => ScmiProtocolKind::Voltage,
+            0x18 => ScmiProtocolKind::Powercap,
+            0x19 => ScmiProtocolKind::Pinctrl,
+            _ => return None,
+        })
+    }
+
+    /// Dedicated channel mailboxes, if the protocol doesn't share the
+    /// agent's
+    pub fn mailboxes(&self) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        self.node.phandle_args("mboxes", "#mbox-cells")
+    }
+
+    /// Dedicated channel shared memory, translated to CPU physical
+    /// addresses
+    pub fn shmem(&self) -> impl Iterator<Item = (u64, u64)> + 'b {
+        shmem(self.node)
+    }
+}
+
+/// Represents the SCMI node
+#[derive(Debug, Clone, Copy)]
+pub struct Scmi<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Scmi<'b, 'a> {
+    /// The SCMI node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// Transport, from `compatible`
+    pub fn transport(&self) -> Option<ScmiTransport> {
+        let smc_id = || self.node.u32_property("arm,smc-id");
+        self.node.compatible()?.all().find_map(|c| match c {
+            "arm,scmi" => Some(ScmiTransport::Mailbox),
+            "arm,scmi-smc" | "arm,scmi-smc-param" => Some(ScmiTransport::Smc { smc_id: smc_id() }),
+            "linaro,scmi-optee" => Some(ScmiTransport::Optee),
+            "arm,scmi-virtio" => Some(ScmiTransport::Virtio),
+            _ => None,
+        })
+    }
+
+    /// Transmit (and optional receive) channel mailboxes, named by
+    /// `mbox-names`
+    pub fn mailboxes(&self) -> impl Iterator<Item = (Option<&'a str>, PhandleArgs<'b, 'a>)> + 'b {
+        let node = self.node;
+        node.phandle_args("mboxes", "#mbox-cells").map(move |mbox| (node.string_at("mbox-names", mbox.index), mbox))
+    }
+
+    /// Shared memory of the channels, translated to CPU physical addresses
+    pub fn shmem(&self) -> impl Iterator<Item = (u64, u64)> + 'b {
+        shmem(self.node)
+    }
+
+    /// Available protocol children
+    pub fn protocols(&self) -> impl Iterator<Item = ScmiProtocol<'b, 'a>> + 'b {
+        self.node.children().filter(|child| child.is_available()).filter_map(|node| {
+            let id = node.u32_property("reg")?;
+            Some(ScmiProtocol { node, id })
+        })
+    }
+
+    /// Protocol child with ID `id`
+    pub fn protocol(&self, id: u32) -> Option<ScmiProtocol<'b, 'a>> {
+        self.protocols().find(|protocol| protocol.id == id)
+    }
+}
+
+/// Represents the SCPI node
+#[derive(Debug, Clone, Copy)]
+pub struct Scpi<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Scpi<'b, 'a> {
+    /// The SCPI node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// Whether the firmware predates SCPI 1.0, `arm,scpi-pre-1.0`
+    pub fn pre_1_0(&self) -> bool {
+        self.node.compatible().is_some_and(|c| c.all().any(|c| c == "arm,scpi-pre-1.0"))
+    }
+
+    /// Channel mailboxes
+    pub fn mailboxes(&self) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        self.node.phandle_args("mboxes", "#mbox-cells")
+    }
+
+    /// Shared memory of the channels, translated to CPU physical addresses
+    pub fn shmem(&self) -> impl Iterator<Item = (u64, u64)> + 'b {
+        shmem(self.node)
+    }
+
+    /// `arm,scpi-clocks` child
+    pub fn clocks(&self) -> Option<FdtNode<'b, 'a>> {
+        self.child("arm,scpi-clocks")
+    }
+
+    /// `arm,scpi-sensors` child
+    pub fn sensors(&self) -> Option<FdtNode<'b, 'a>> {
+        self.child("arm,scpi-sensors")
+    }
+
+    /// `arm,scpi-power-domains` child
+    pub fn power_domains(&self) -> Option<FdtNode<'b, 'a>> {
+        self.child("arm,scpi-power-domains")
+    }
+
+    fn child(&self, compatible: &str) -> Option<FdtNode<'b, 'a>> {
+        self.node.children().find(|child| child.compatible().is_some_and(|c| c.all().any(|c| c == compatible)))
+    }
+}
+
+/// Represents the SDEI node
+#[derive(Debug, Clone, Copy)]
+pub struct Sdei<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Sdei<'b, 'a> {
+    /// The SDEI node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// `method` used to call TF-A
+    pub fn method(&self) -> Option<PsciMethod> {
+        method(self.node)
+    }
+}
+
+/// Regions of the `shmem` nodes of `node`
+fn shmem<'b, 'a: 'b>(node: FdtNode<'b, 'a>) -> impl Iterator<Item = (u64, u64)> + 'b {
+    node.phandle_args_fixed("shmem", 0).filter_map(|shmem| {
+        let (address, size) = shmem.node.reg()?.next_raw()?;
+        Some((shmem.node.translate_address(address)?, size))
+    })
+}
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Linux kernel simple-framebuffer nodes
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/display/simple-framebuffer.yaml
+
+use crate::bindings::{Clock, PowerDomain, Supply};
+use crate::node::FdtNode;
+
+/// Pixel layout of a framebuffer, most significant bits first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// `r5g6b5`
+    R5G6B5,
+    /// `r5g5b5a1`
+    R5G5B5A1,
+    /// `x1r5g5b5`
+    X1R5G5B5,
+    /// `a1r5g5b5`
+    A1R5G5B5,
+    /// `r8g8b8`
+    R8G8B8,
+    /// `x8r8g8b8`
+    X8R8G8B8,
+    /// `a8r8g8b8`
+    A8R8G8B8,
+    /// `x8b8g8r8`
+    X8B8G8R8,
+    /// `a8b8g8r8`
+    A8B8G8R8,
+    /// `x2r10g10b10`
+    X2R10G10B10,
+    /// `a2r10g10b10`
+    A2R10G10B10,
+}
+
+impl PixelFormat {
+    /// Parses a `format` string
+    pub fn parse(format: &str) -> Option<Self> {
+        Some(match format {
+            "r5g6b5" => Self::R5G6B5,
+            "r5g5b5a1" => Self::R5G5B5A1,
+            "x1r5g5b5" => Self::X1R5G5B5,
+            "a1r5g5b5" => Self::A1R5G5B5,
+            "r8g8b8" => Self::R8G8B8,
+            "x8r8g8b8" => Self::X8R8G8B8,
+            "a8r8g8b8" => Self::A8R8G8B8,
+            "x8b8g8r8" => Self::X8B8G8R8,
+            "a8b8g8r8" => Self::A8B8G8R8,
+            "x2r10g10b10" => Self::X2R10G10B10,
+            "a2r10g10b10" => Self::A2R10G10B10,
+            _ => return None,
+        })
+    }
+
+    /// Bits per pixel
+    pub fn bits_per_pixel(self) -> u32 {
+        match self {
+            Self::R5G6B5 | Self::R5G5B5A1 | Self::X1R5G5B5 | Self::A1R5G5B5 => 16,
+            Self::R8G8B8 => 24,
+            _ => 32,
+        }
+    }
+}
+
+/// Represents a `simple-framebuffer` node with specific helper methods
+#[derive(Debug, Clone, Copy)]
+pub struct Framebuffer<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Framebuffer<'b, 'a> {
+    /// Wraps `node` if it is compatible with `simple-framebuffer`
+    pub fn new(node: FdtNode<'b, 'a>) -> Option<Self> {
+        node.compatible()?.all().any(|c| c == "simple-framebuffer").then_some(Self { node })
+    }
+
+    /// The framebuffer node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// CPU physical address of the framebuffer memory
+    pub fn base(&self) -> Option<u64> {
+        let (address, _) = self.node.reg()?.next_raw()?;
+        self.node.translate_address(address)
+    }
+
+    /// Size of the framebuffer memory
+    pub fn size(&self) -> Option<u64> {
+        Some(self.node.reg()?.next_raw()?.1)
+    }
+
+    /// `width` in pixels
+    pub fn width(&self) -> Option<u32> {
+        self.node.u32_property("width")
+    }
+
+    /// `height` in pixels
+    pub fn height(&self) -> Option<u32> {
+        self.node.u32_property("height")
+    }
+
+    /// `stride`: bytes per line
+    pub fn stride(&self) -> Option<u32> {
+        self.node.u32_property("stride")
+    }
+
+    /// Raw `format` string
+    pub fn format_str(&self) -> Option<&'a str> {
+        self.node.property("format")?.as_str()
+    }
+
+    /// `format` as a [`PixelFormat`], `None` for an unknown format
+    pub fn format(&self) -> Option<PixelFormat> {
+        PixelFormat::parse(self.format_str()?)
+    }
+
+    /// Clocks that must be kept on while the framebuffer is in use
+    pub fn clocks(&self) -> impl Iterator<Item = Clock<'b, 'a>> + 'b {
+        self.node.clocks()
+    }
+
+    /// Power domains that must be kept on while the framebuffer is in use
+    pub fn power_domains(&self) -> impl Iterator<Item = PowerDomain<'b, 'a>> + 'b {
+        self.node.power_domains()
+    }
+
+    /// Regulators from the `*-supply` properties
+    pub fn regulators(&self) -> impl Iterator<Item = Supply<'b, 'a>> + 'b {
+        self.node.supplies()
+    }
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! The `/hypervisor` node of virtual machines
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/arm/xen.txt
+
+use crate::chosen::UefiParams;
+use crate::node::FdtNode;
+use crate::phandle::PhandleArgs;
+
+/// Represents the `/hypervisor` node with specific helper methods
+#[derive(Debug, Clone, Copy)]
+pub struct Hypervisor<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Hypervisor<'b, 'a> {
+    /// The `/hypervisor` node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// Xen view, if compatible with `xen,xen`
+    pub fn xen(&self) -> Option<Xen<'b, 'a>> {
+        self.node.compatible()?.all().any(|c| c == "xen,xen").then_some(Xen { node: self.node })
+    }
+}
+
+/// A Xen `/hypervisor` node
+#[derive(Debug, Clone, Copy)]
+pub struct Xen<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Xen<'b, 'a> {
+    /// Xen version from the `xen,xen-<version>` compatible, e.g. `4.17`
+    pub fn version(&self) -> Option<&'a str> {
+        self.node.compatible()?.all().find_map(|c| c.strip_prefix("xen,xen-"))
+    }
+
+    /// Grant table region, the first `reg` entry
+    pub fn grant_table(&self) -> Option<(u64, u64)> {
+        self.regions().next()
+    }
+
+    /// Extended regions the guest may map foreign pages into, the `reg`
+    /// entries after the grant table
+    pub fn extended_regions(&self) -> impl Iterator<Item = (u64, u64)> + 'b {
+        self.regions().skip(1)
+    }
+
+    /// Event channel upcall interrupt, a PPI
+    pub fn event_channel_irq(&self) -> Option<PhandleArgs<'b, 'a>> {
+        self.node.interrupt_specifiers().next()
+    }
+
+    /// UEFI system table and memory map from `/hypervisor/uefi`, for dom0
+    /// booted through Xen's EFI loader
+    pub fn uefi(&self) -> Option<UefiParams> {
+        let uefi = self.node.children().find(|child| child.name == "uefi")?;
+        UefiParams::from_node(uefi, "xen")
+    }
+
+    fn regions(&self) -> impl Iterator<Item = (u64, u64)> + 'b {
+        let node = self.node;
+        let mut reg = node.reg();
+        core::iter::from_fn(move || reg.as_mut()?.next_raw())
+            .filter_map(move |(address, size)| Some((node.translate_address(address)?, size)))
+    }
+}
@@ -9,9 +9,27 @@ pub mod chosen;
 pub mod memory;
 pub mod reserved_memory;
 pub mod interrupt;
+pub mod serial;
+pub mod framebuffer;
+pub mod firmware;
+pub mod hypervisor;
+pub mod psci;
+pub mod riscv;
+pub mod thermal;
+pub mod timer;
+pub mod virtio;
 
 pub use chosen::Chosen;
 pub use memory::Memory;
 pub use reserved_memory::ReservedMemory;
 pub use interrupt::InterruptController;
 pub use dice::Dice;
+pub use serial::Uart;
+pub use framebuffer::Framebuffer;
+pub use psci::{IdleStates, Psci};
+pub use riscv::RiscvHart;
+pub use timer::{Timer, TimerMem};
+pub use thermal::ThermalZone;
+pub use firmware::{Firmware, Scpi};
+pub use hypervisor::Hypervisor;
+pub use virtio::VirtioMmio;
@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! PSCI firmware node, CPU enable methods and idle states
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/arm/psci.yaml
+//! and https://www.kernel.org/doc/Documentation/devicetree/bindings/cpu/idle-states.yaml
+
+use crate::node::{read_cells, FdtNode};
+use crate::standard_nodes::Cpu;
+
+/// Conduit used to call PSCI functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PsciMethod {
+    /// `smc`: Secure Monitor Call
+    Smc,
+    /// `hvc`: Hypervisor Call
+    Hvc,
+}
+
+/// PSCI version announced by `compatible`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PsciVersion {
+    /// `arm,psci`: function IDs come from the node
+    V0_1,
+    /// `arm,psci-0.2`: standard function IDs
+    V0_2,
+    /// `arm,psci-1.0`
+    V1_0,
+}
+
+/// Represents the `/psci` node with specific helper methods
+#[derive(Debug, Clone, Copy)]
+pub struct Psci<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Psci<'b, 'a> {
+    /// The PSCI node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// `method`, `None` if absent or unknown
+    pub fn method(&self) -> Option<PsciMethod> {
+        method(self.node)
+    }
+
+    /// Newest version listed in `compatible`
+    pub fn version(&self) -> Option<PsciVersion> {
+        self.node
+            .compatible()?
+            .all()
+            .filter_map(|c| match c {
+                "arm,psci" => Some(PsciVersion::V0_1),
+                "arm,psci-0.2" => Some(PsciVersion::V0_2),
+                "arm,psci-1.0" => Some(PsciVersion::V1_0),
+                _ => None,
+            })
+            .max()
+    }
+
+    /// `cpu_on` function ID, for PSCI 0.1
+    pub fn cpu_on(&self) -> Option<u32> {
+        self.node.u32_property("cpu_on")
+    }
+
+    /// `cpu_off` function ID, for PSCI 0.1
+    pub fn cpu_off(&self) -> Option<u32> {
+        self.node.u32_property("cpu_off")
+    }
+
+    /// `cpu_suspend` function ID, for PSCI 0.1
+    pub fn cpu_suspend(&self) -> Option<u32> {
+        self.node.u32_property("cpu_suspend")
+    }
+
+    /// `migrate` function ID, for PSCI 0.1
+    pub fn migrate(&self) -> Option<u32> {
+        self.node.u32_property("migrate")
+    }
+}
+
+/// How a secondary CPU is brought up, from its `enable-method`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnableMethod<'a> {
+    /// `psci`: through the PSCI `CPU_ON` call
+    Psci,
+    /// `spin-table`: by writing the entry point to `cpu-release-addr`
+    SpinTable {
+        /// `cpu-release-addr`, if present
+        release_addr: Option<u64>,
+    },
+    /// Any other method
+    Other(&'a str),
+}
+
+/// Represents the `/cpus/idle-states` container
+#[derive(Debug, Clone, Copy)]
+pub struct IdleStates<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> IdleStates<'b, 'a> {
+    /// The idle-states node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// `entry-method`: how the states are entered
+    pub fn entry_method(&self) -> Option<&'a str> {
+        self.node.property("entry-method")?.as_str()
+    }
+
+    /// The states are entered through PSCI `CPU_SUSPEND`
+    pub fn is_psci(&self) -> bool {
+        self.entry_method() == Some("psci")
+    }
+
+    /// Available `arm,idle-state` and `riscv,idle-state` children
+    pub fn states(&self) -> impl Iterator<Item = IdleState<'b, 'a>> + 'b {
+        self.node
+            .children()
+            .filter(|node| node.is_available())
+            .filter(|node| {
+                node.compatible()
+                    .is_some_and(|c| c.all().any(|c| matches!(c, "arm,idle-state" | "riscv,idle-state")))
+            })
+            .map(|node| IdleState { node })
+    }
+}
+
+/// An idle state node referenced by `cpu-idle-states`
+#[derive(Debug, Clone, Copy)]
+pub struct IdleState<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> IdleState<'b, 'a> {
+    /// `idle-state-name`, or else the node name
+    pub fn name(&self) -> &'a str {
+        self.node.property("idle-state-name").and_then(|p| p.as_str()).unwrap_or(self.node.name)
+    }
+
+    /// `arm,psci-suspend-param`: power state passed to `CPU_SUSPEND`
+    pub fn psci_suspend_param(&self) -> Option<u32> {
+        self.node.u32_property("arm,psci-suspend-param")
+    }
+
+    /// `entry-latency-us`
+    pub fn entry_latency_us(&self) -> Option<u32> {
+        self.node.u32_property("entry-latency-us")
+    }
+
+    /// `exit-latency-us`
+    pub fn exit_latency_us(&self) -> Option<u32> {
+        self.node.u32_property("exit-latency-us")
+    }
+
+    /// `min-residency-us`
+    pub fn min_residency_us(&self) -> Option<u32> {
+        self.node.u32_property("min-residency-us")
+    }
+
+    /// `wakeup-latency-us`, defaulting to entry plus exit latency
+    pub fn wakeup_latency_us(&self) -> Option<u32> {
+        self.node.u32_property("wakeup-latency-us")
+            .or_else(|| self.entry_latency_us()?.checked_add(self.exit_latency_us()?))
+    }
+
+    /// `local-timer-stop`: the CPU local timer stops in this state
+    pub fn local_timer_stop(&self) -> bool {
+        self.node.property("local-timer-stop").is_some()
+    }
+}
+
+/// Conduit from the `method` property of SMCCC based firmware nodes
+pub(crate) fn method(node: FdtNode<'_, '_>) -> Option<PsciMethod> {
+    match node.property("method")?.as_str()? {
+        "smc" => Some(PsciMethod::Smc),
+        "hvc" => Some(PsciMethod::Hvc),
+        _ => None,
+    }
+}
+
+impl<'b, 'a: 'b> Cpu<'b, 'a> {
+    /// The CPU node
+    pub fn node(self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// `enable-method`
+    pub fn enable_method(self) -> Option<EnableMethod<'a>> {
+        Some(match self.node.property("enable-method")?.as_str()? {
+            "psci" => EnableMethod::Psci,
+            "spin-table" => EnableMethod::SpinTable {
+                release_addr: self.node.property("cpu-release-addr").map(|p| read_cells(p.value)),
+            },
+            other => EnableMethod::Other(other),
+        })
+    }
+
+    /// Idle states from `cpu-idle-states`, shallowest first; disabled
+    /// states are skipped
+    pub fn idle_states(self) -> impl Iterator<Item = IdleState<'b, 'a>> + 'b {
+        self.node
+            .phandle_args_fixed("cpu-idle-states", 0)
+            .map(|state| state.node)
+            .filter(|node| node.is_available())
+            .map(|node| IdleState { node })
+    }
+}
@@ -7,8 +7,11 @@
 //!
 //! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/reserved-memory/reserved-memory.yaml
 
+use crate::memory_map::{MemoryKind, MemoryMap, MemoryRange};
 use crate::node::FdtNode;
+use crate::parsing::{BigEndianU32, BigEndianU64};
 use crate::standard_nodes::RegIter;
+use crate::ReservedMemoryError;
 
 /// Represents the `/reserved-memory/*` node, it status is ok and have `reg` property
 #[derive(Debug, Clone, Copy)]
@@ -19,8 +22,8 @@ pub struct ValidReservedMemoryNode<'b, 'a> {
 
 impl <'b, 'a: 'b> ValidReservedMemoryNode<'b, 'a> {
     /// Returns an iterator over all of the valid regs
-    pub fn regions(&self) -> RegIter<'a> {
-        self.node.reg().unwrap()
+    pub fn regions(&self) -> Result<RegIter<'a>, ReservedMemoryError> {
+        parse_reg(self.node, "reg").ok_or(ReservedMemoryError::BadReg)
     }
 
     /// return nomap property
@@ -35,20 +38,28 @@ impl <'b, 'a: 'b> ValidReservedMemoryNode<'b, 'a> {
 pub struct DynamicReservedMemoryNode<'b, 'a> {
     /// node
     pub node: FdtNode<'b, 'a>,
-    size: usize,
 }
 
 impl <'b, 'a: 'b> DynamicReservedMemoryNode<'b, 'a> {
-    /// return size
-    #[inline]
-    pub fn size(&self) -> usize {
-        self.size
+    /// return size, which has to be `#size-cells` long and nonzero
+    pub fn size(&self) -> Result<u64, ReservedMemoryError> {
+        let cells = self.node.parent_cell_sizes().size_cells;
+        self.node
+            .property("size")
+            .and_then(|p| read_cells(p.value, cells))
+            .filter(|&size| size != 0)
+            .ok_or(ReservedMemoryError::BadSize)
     }
 
-    /// return alignment
-    pub fn alignment(&self) -> usize {
+    /// return alignment, which has to be `#address-cells` long and a power
+    /// of two
+    pub fn alignment(&self) -> Result<u64, ReservedMemoryError> {
         // if no alignment, default is 0
-        self.node.property("alignment").map(|p| p.as_usize().unwrap()).unwrap_or(0)
+        let Some(prop) = self.node.property("alignment") else { return Ok(0) };
+        let cells = self.node.parent_cell_sizes().address_cells;
+        read_cells(prop.value, cells)
+            .filter(|&align| align == 0 || align.is_power_of_two())
+            .ok_or(ReservedMemoryError::BadAlignment)
     }
 
     /// return nomap
@@ -59,8 +70,11 @@ impl <'b, 'a: 'b> DynamicReservedMemoryNode<'b, 'a> {
     /// alloc-ranges
     /// Address and Length pairs. Specifies regions of memory that are
     /// acceptable to allocate from.
-    pub fn alloc_ranges(&self) -> Option<RegIter<'a>> {
-        self.node.property("alloc-ranges").map(|p| p.as_reg(self.node.parent_cell_sizes()).unwrap())
+    pub fn alloc_ranges(&self) -> Result<Option<RegIter<'a>>, ReservedMemoryError> {
+        if self.node.property("alloc-ranges").is_none() {
+            return Ok(None);
+        }
+        parse_reg(self.node, "alloc-ranges").map(Some).ok_or(ReservedMemoryError::BadAllocRanges)
     }
 
     /// reusable property
@@ -75,6 +89,204 @@ impl <'b, 'a: 'b> DynamicReservedMemoryNode<'b, 'a> {
 
 }
 
+/// Represents an available `/reserved-memory/*` node that can't be used
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidReservedMemoryNode<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+    /// Why the node can't be used
+    pub reason: ReservedMemoryError,
+}
+
+/// An available `/reserved-memory/*` node, sorted by how it is set up
+#[derive(Debug, Clone, Copy)]
+pub enum ReservedRegion<'b, 'a> {
+    /// Region at a fixed address
+    Static(ValidReservedMemoryNode<'b, 'a>),
+    /// Region to be allocated
+    Dynamic(DynamicReservedMemoryNode<'b, 'a>),
+    /// Node that describes no usable region
+    Invalid(InvalidReservedMemoryNode<'b, 'a>),
+}
+
+/// Reads `name` as `reg`-style address/size pairs, `None` if it is empty or
+/// doesn't fit the parent's cell sizes
+fn parse_reg<'a>(node: FdtNode<'_, 'a>, name: &str) -> Option<RegIter<'a>> {
+    let prop = node.property(name)?;
+    let sizes = node.parent_cell_sizes();
+    let entry = (sizes.address_cells + sizes.size_cells) * 4;
+    if sizes.address_cells == 0 || entry == 0 || prop.value.is_empty() || prop.value.len() % entry != 0 {
+        return None;
+    }
+    prop.as_reg(sizes)
+}
+
+/// Sorts a region node, given the result of [`ReservedMemory::check`] on
+/// its parent
+fn classify<'b, 'a: 'b>(node: FdtNode<'b, 'a>, root: Result<(), ReservedMemoryError>) -> ReservedRegion<'b, 'a> {
+    if let Err(reason) = root {
+        return ReservedRegion::Invalid(InvalidReservedMemoryNode { node, reason });
+    }
+
+    if node.property("reg").is_some() {
+        match parse_reg(node, "reg") {
+            Some(_) => ReservedRegion::Static(ValidReservedMemoryNode { node }),
+            None => ReservedRegion::Invalid(InvalidReservedMemoryNode { node, reason: ReservedMemoryError::BadReg }),
+        }
+    } else if node.property("size").is_some() {
+        let dynamic = DynamicReservedMemoryNode { node };
+        match dynamic.size().and(dynamic.alignment()).and(dynamic.alloc_ranges()) {
+            Ok(_) => ReservedRegion::Dynamic(dynamic),
+            Err(reason) => ReservedRegion::Invalid(InvalidReservedMemoryNode { node, reason }),
+        }
+    } else {
+        ReservedRegion::Invalid(InvalidReservedMemoryNode { node, reason: ReservedMemoryError::MissingRegOrSize })
+    }
+}
+
+/// Reads a value of exactly `cells` cells
+fn read_cells(value: &[u8], cells: usize) -> Option<u64> {
+    match (cells, value.len()) {
+        (1, 4) => BigEndianU32::from_bytes(value).map(|v| u64::from(v.get())),
+        (2, 8) => BigEndianU64::from_bytes(value).map(|v| v.get()),
+        _ => None,
+    }
+}
+
+/// Handler a `/reserved-memory` region is set up by, picked from its
+/// `compatible` like Linux's `RESERVEDMEM_OF_DECLARE` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservedMemoryHandler<'a> {
+    /// `shared-dma-pool` with `reusable`: a CMA area
+    Cma,
+    /// `shared-dma-pool` without `reusable`: a coherent DMA pool
+    DmaPool,
+    /// `restricted-dma-pool`: a bounce buffer pool for restricted DMA
+    RestrictedDmaPool,
+    /// `ramoops`: persistent storage for kernel logs
+    Ramoops,
+    /// First compatible, when none of the above is listed
+    Other(&'a str),
+}
+
+/// Any available `/reserved-memory/*` node, static or dynamic
+///
+/// Its `reg` or `size` is read through [`ReservedMemoryNode::region`].
+#[derive(Debug, Clone, Copy)]
+pub struct ReservedMemoryNode<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> ReservedMemoryNode<'b, 'a> {
+    /// Handler for the region, `None` without a `compatible`
+    pub fn handler(&self) -> Option<ReservedMemoryHandler<'a>> {
+        let compatible = self.node.compatible()?;
+        let handler = compatible.all().find_map(|c| match c {
+            "shared-dma-pool" if self.node.property("reusable").is_some() => Some(ReservedMemoryHandler::Cma),
+            "shared-dma-pool" => Some(ReservedMemoryHandler::DmaPool),
+            "restricted-dma-pool" => Some(ReservedMemoryHandler::RestrictedDmaPool),
+            "ramoops" => Some(ReservedMemoryHandler::Ramoops),
+            _ => None,
+        });
+
+        Some(handler.unwrap_or(ReservedMemoryHandler::Other(compatible.first())))
+    }
+
+    /// The node sorted into static, dynamic or invalid, like
+    /// [`ReservedMemory::regions`] does
+    pub fn region(&self) -> ReservedRegion<'b, 'a> {
+        let root = match self.node.parent() {
+            Some(node) => ReservedMemory { node }.check(),
+            None => Err(ReservedMemoryError::MissingCells),
+        };
+        classify(self.node, root)
+    }
+
+    /// `linux,cma-default`: the default CMA area for devices without their
+    /// own `memory-region`
+    pub fn cma_default(&self) -> bool {
+        self.node.property("linux,cma-default").is_some()
+    }
+
+    /// `linux,dma-default`: the default coherent DMA pool for devices
+    /// without their own `memory-region`
+    pub fn dma_default(&self) -> bool {
+        self.node.property("linux,dma-default").is_some()
+    }
+
+    /// `ramoops` settings, if this is a ramoops region
+    pub fn ramoops(&self) -> Option<Ramoops<'b, 'a>> {
+        (self.handler()? == ReservedMemoryHandler::Ramoops).then_some(Ramoops { node: self.node })
+    }
+}
+
+impl<'b, 'a> From<ValidReservedMemoryNode<'b, 'a>> for ReservedMemoryNode<'b, 'a> {
+    fn from(valid: ValidReservedMemoryNode<'b, 'a>) -> Self {
+        Self { node: valid.node }
+    }
+}
+
+impl<'b, 'a> From<DynamicReservedMemoryNode<'b, 'a>> for ReservedMemoryNode<'b, 'a> {
+    fn from(dynamic: DynamicReservedMemoryNode<'b, 'a>) -> Self {
+        Self { node: dynamic.node }
+    }
+}
+
+/// `ramoops` reserved memory region
+///
+/// Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/reserved-memory/ramoops.yaml
+#[derive(Debug, Clone, Copy)]
+pub struct Ramoops<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Ramoops<'b, 'a> {
+    /// `record-size`: size of each oops/panic record, 0 if disabled
+    pub fn record_size(&self) -> usize {
+        self.size_property("record-size")
+    }
+
+    /// `console-size`: size of the console log, 0 if disabled
+    pub fn console_size(&self) -> usize {
+        self.size_property("console-size")
+    }
+
+    /// `ftrace-size`: size of the ftrace log, 0 if disabled
+    pub fn ftrace_size(&self) -> usize {
+        self.size_property("ftrace-size")
+    }
+
+    /// `pmsg-size`: size of the user space message log, 0 if disabled
+    pub fn pmsg_size(&self) -> usize {
+        self.size_property("pmsg-size")
+    }
+
+    /// `ecc-size`: ECC bytes per block, 0 if ECC is off
+    pub fn ecc_size(&self) -> usize {
+        self.size_property("ecc-size")
+    }
+
+    /// `mem-type`: memory mapping type (0 write-combined, 1 uncached, 2 cached)
+    pub fn mem_type(&self) -> usize {
+        self.size_property("mem-type")
+    }
+
+    /// `unbuffered`: map the region without write buffering
+    pub fn unbuffered(&self) -> bool {
+        self.node.property("unbuffered").is_some()
+    }
+
+    /// `max-reason`: most verbose kmsg dump reason stored
+    pub fn max_reason(&self) -> Option<usize> {
+        self.node.property("max-reason")?.as_usize()
+    }
+
+    fn size_property(&self, name: &str) -> usize {
+        self.node.property(name).and_then(|p| p.as_usize()).unwrap_or(0)
+    }
+}
+
 /// Represents the `/reserved-memory` node with specific helper methods
 #[derive(Debug, Clone, Copy)]
 pub struct ReservedMemory<'b, 'a> {
@@ -82,48 +294,213 @@ pub struct ReservedMemory<'b, 'a> {
 }
 
 impl<'b, 'a: 'b> ReservedMemory<'b, 'a> {
-    /// Contains the bootargs, if they exist
-    pub(crate) fn check_root(self) -> Result<(), ()> {
-        // reserved mem root should have address-cells and size-cells and 
-        // ranges property
-        if self.node.property("#address-cells").is_none() {
-            return Err(());
-        }
-        if self.node.property("#size-cells").is_none() {
-            return Err(());
+    /// Checks the node has `#address-cells`, `#size-cells` and `ranges`,
+    /// with the same cell sizes as the root node
+    ///
+    /// Linux ignores every region when this fails.
+    pub fn check(self) -> Result<(), ReservedMemoryError> {
+        if self.node.property("#address-cells").is_none() || self.node.property("#size-cells").is_none() {
+            return Err(ReservedMemoryError::MissingCells);
         }
         if self.node.property("ranges").is_none() {
-            return Err(());
+            return Err(ReservedMemoryError::MissingRanges);
         }
 
         // check size and address cell size is eque root node cell
         if self.node.cell_sizes() != self.node.parent_cell_sizes()  {
-            return Err(());
+            return Err(ReservedMemoryError::CellsMismatch);
         }
         Ok(())
     }
 
+    /// Return every available region node, sorted into static, dynamic and
+    /// invalid ones
+    ///
+    /// All of them are invalid if [`ReservedMemory::check`] fails.
+    pub fn regions(self) -> impl Iterator<Item = ReservedRegion<'b, 'a>> + 'b {
+        let root = self.check();
+        self.node.children().filter(|node| node.is_available()).map(move |node| classify(node, root))
+    }
+
+    /// Return all available region nodes, static and dynamic
+    pub fn nodes(self) -> impl Iterator<Item = ReservedMemoryNode<'b, 'a>> + 'b {
+        self.node.children().filter(|node| node.is_available()).map(|node| ReservedMemoryNode { node })
+    }
+
+    /// Default CMA area, the reusable `shared-dma-pool` marked
+    /// `linux,cma-default`
+    pub fn cma_default(self) -> Option<ReservedMemoryNode<'b, 'a>> {
+        self.nodes().find(|n| n.cma_default() && n.handler() == Some(ReservedMemoryHandler::Cma))
+    }
+
+    /// Default coherent DMA pool, the non-reusable `shared-dma-pool` marked
+    /// `linux,dma-default`
+    pub fn dma_default(self) -> Option<ReservedMemoryNode<'b, 'a>> {
+        self.nodes().find(|n| n.dma_default() && n.handler() == Some(ReservedMemoryHandler::DmaPool))
+    }
+
     /// Return valid ReservedNode
     pub fn valid_reserved_nodes(self) -> impl Iterator<Item = ValidReservedMemoryNode<'b, 'a>> + 'b {
-        self.node.children().filter_map(|node| {
-            if node.is_available() {
-                if node.reg().is_some() {
-                    return Some(ValidReservedMemoryNode { node });
-                }
-            }
-            None
+        self.regions().filter_map(|region| match region {
+            ReservedRegion::Static(node) => Some(node),
+            _ => None,
         })
     }
 
     /// Return dynamic nodes
     pub fn dynamic_nodes(self) -> impl Iterator<Item = DynamicReservedMemoryNode<'b, 'a>> + 'b {
-        self.node.children().filter_map(|node| {
-            if node.is_available() {
-                if let Some(size) = node.property("size") && node.reg().is_none() {
-                    return Some(DynamicReservedMemoryNode { node, size: size.as_usize().unwrap() });
+        self.regions().filter_map(|region| match region {
+            ReservedRegion::Dynamic(node) => Some(node),
+            _ => None,
+        })
+    }
+
+    /// Return nodes that can't be used, with the reason
+    pub fn invalid_nodes(self) -> impl Iterator<Item = InvalidReservedMemoryNode<'b, 'a>> + 'b {
+        self.regions().filter_map(|region| match region {
+            ReservedRegion::Invalid(node) => Some(node),
+            _ => None,
+        })
+    }
+}
+
+/// Where in a free range a dynamic region is placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AllocPolicy {
+    /// Highest suitable address first, the memblock default
+    #[default]
+    TopDown,
+    /// Lowest suitable address first, like `memblock_set_bottom_up(true)`
+    BottomUp,
+}
+
+/// Places the dynamic `/reserved-memory` regions in a [`MemoryMap`]
+///
+/// Follows `__reserved_mem_alloc_size`: each region is tried in every
+/// `alloc-ranges` entry in turn (or anywhere when there are none), and is
+/// carved out of the map's usable memory as `Reserved`, or `NoMap` for
+/// `no-map` regions, so later regions and the caller see it taken. Build the
+/// map with the static regions first, e.g. with
+/// [`LinuxFdt::memory_map`](crate::LinuxFdt::memory_map).
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicAllocator {
+    policy: AllocPolicy,
+    default_alignment: u64,
+    cma_alignment: u64,
+}
+
+/// A dynamic region and where it was placed
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicAllocation<'b, 'a> {
+    /// node
+    pub node: DynamicReservedMemoryNode<'b, 'a>,
+    /// Placed range, or why the region couldn't be placed
+    pub result: Result<MemoryRange, ReservedMemoryError>,
+}
+
+impl Default for DynamicAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamicAllocator {
+    /// First page, never handed out, as in memblock
+    const LOWEST: u64 = 0x1000;
+
+    /// Top-down allocator with Linux's arm64 defaults: 64 byte
+    /// (`SMP_CACHE_BYTES`) alignment and 2 MiB (`CMA_MIN_ALIGNMENT_BYTES`)
+    /// for reusable `shared-dma-pool` regions
+    pub fn new() -> Self {
+        Self { policy: AllocPolicy::TopDown, default_alignment: 64, cma_alignment: 0x20_0000 }
+    }
+
+    /// Sets the placement policy
+    pub fn policy(mut self, policy: AllocPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Alignment used for regions without an `alignment` property
+    pub fn default_alignment(mut self, alignment: u64) -> Self {
+        self.default_alignment = alignment;
+        self
+    }
+
+    /// Minimum alignment of reusable `shared-dma-pool` (CMA) regions
+    pub fn cma_alignment(mut self, alignment: u64) -> Self {
+        self.cma_alignment = alignment;
+        self
+    }
+
+    /// Places every dynamic region of `reserved`, in devicetree order
+    pub fn allocate_all<'m, 'b, 'a: 'b>(
+        &'m self,
+        reserved: ReservedMemory<'b, 'a>,
+        map: &'m mut MemoryMap<'_>,
+    ) -> impl Iterator<Item = DynamicAllocation<'b, 'a>> + 'm
+    where
+        'b: 'm,
+    {
+        reserved.dynamic_nodes().map(move |node| DynamicAllocation { node, result: self.allocate(&node, map) })
+    }
+
+    /// Places one dynamic region, marking it in `map`
+    pub fn allocate(
+        &self,
+        node: &DynamicReservedMemoryNode<'_, '_>,
+        map: &mut MemoryMap<'_>,
+    ) -> Result<MemoryRange, ReservedMemoryError> {
+        let size = node.size()?;
+        let mut align = match node.alignment()? {
+            0 => self.default_alignment.max(1),
+            align => align,
+        };
+        if node.shared_dma_pool() && node.reusable() {
+            align = align.max(self.cma_alignment);
+        }
+        if !align.is_power_of_two() {
+            return Err(ReservedMemoryError::BadAlignment);
+        }
+
+        let found = match node.alloc_ranges()? {
+            Some(mut ranges) => core::iter::from_fn(|| ranges.next_raw())
+                .find_map(|(start, len)| self.find(map, size, align, start, start.saturating_add(len))),
+            None => self.find(map, size, align, 0, u64::MAX),
+        };
+        let start = found.ok_or(ReservedMemoryError::NoMemory)?;
+
+        let (kind, marked) = match node.nomap() {
+            true => (MemoryKind::NoMap, map.mark_no_map(start, size)),
+            false => (MemoryKind::Reserved, map.reserve(start, size)),
+        };
+        marked.map_err(|_| ReservedMemoryError::NoSpace)?;
+
+        Ok(MemoryRange { start, size, kind })
+    }
+
+    /// Finds `size` bytes of usable memory aligned to `align` within
+    /// `lo..hi`
+    fn find(&self, map: &MemoryMap<'_>, size: u64, align: u64, lo: u64, hi: u64) -> Option<u64> {
+        let fit = |r: MemoryRange| {
+            let start = r.start.max(lo).max(Self::LOWEST);
+            let end = r.end().min(hi);
+            match self.policy {
+                AllocPolicy::TopDown => {
+                    let base = end.checked_sub(size)? & !(align - 1);
+                    (base >= start).then_some(base)
+                }
+                AllocPolicy::BottomUp => {
+                    let base = start.checked_next_multiple_of(align)?;
+                    (base.checked_add(size)? <= end).then_some(base)
                 }
             }
-            None
-        })
+        };
+
+        let mut usable = map.ranges().iter().copied().filter(|r| r.kind == MemoryKind::Usable);
+        match self.policy {
+            AllocPolicy::TopDown => usable.rev().find_map(fit),
+            AllocPolicy::BottomUp => usable.find_map(fit),
+        }
     }
 }
@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! RISC-V hart nodes
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/riscv/cpus.yaml
+//! and https://www.kernel.org/doc/Documentation/devicetree/bindings/riscv/extensions.yaml
+
+use crate::node::{read_cells, FdtNode};
+use crate::standard_nodes::Cpu;
+
+/// Extensions implied by the `g` shorthand
+const G_EXTENSIONS: [&str; 7] = ["i", "m", "a", "f", "d", "zicsr", "zifencei"];
+
+/// Virtual memory scheme from `mmu-type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MmuType {
+    /// `riscv,sv32`
+    Sv32,
+    /// `riscv,sv39`
+    Sv39,
+    /// `riscv,sv48`
+    Sv48,
+    /// `riscv,sv57`
+    Sv57,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Extensions<'a> {
+    /// Rest of a `riscv,isa` string after the base
+    String(&'a str),
+    /// `riscv,isa-extensions` string list
+    List(&'a [u8]),
+}
+
+/// The ISA of a hart: base width and extension set
+#[derive(Debug, Clone, Copy)]
+pub struct RiscvIsa<'a> {
+    bits: u32,
+    extensions: Extensions<'a>,
+}
+
+impl<'a> RiscvIsa<'a> {
+    /// Parses a `riscv,isa` string such as `rv64imafdc_zicsr_zicbom`
+    ///
+    /// Like Linux, version numbers are dropped, `g` stands for
+    /// `imafd_zicsr_zifencei` and the bogus `su` some firmware appends to the
+    /// single-letter extensions is ignored.
+    pub fn parse(isa: &'a str) -> Option<Self> {
+        let (bits, rest) = base(isa)?;
+        Some(Self { bits, extensions: Extensions::String(rest) })
+    }
+
+    /// Base integer width, 32 or 64
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Extension names, single letters first for `riscv,isa` strings
+    pub fn extensions(&self) -> impl Iterator<Item = &'a str> + 'a {
+        let (string, list) = match self.extensions {
+            Extensions::String(rest) => (Some(IsaString { rest, implied: 0, after_separator: false }), None),
+            Extensions::List(list) => {
+                let list = list.strip_suffix(&[0]).unwrap_or(list);
+                let names = list.split(|&b| b == 0).filter(move |_| !list.is_empty());
+                (None, Some(names.filter_map(|name| core::str::from_utf8(name).ok())))
+            }
+        };
+
+        string.into_iter().flatten().chain(list.into_iter().flatten())
+    }
+
+    /// Whether extension `name` is present, ignoring case
+    pub fn has(&self, name: &str) -> bool {
+        self.extensions().any(|ext| ext.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Walks the extensions of a `riscv,isa` string
+struct IsaString<'a> {
+    rest: &'a str,
+    implied: usize,
+    after_separator: bool,
+}
+
+impl<'a> Iterator for IsaString<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        loop {
+            if (1..=G_EXTENSIONS.len()).contains(&self.implied) {
+                self.implied += 1;
+                return Some(G_EXTENSIONS[self.implied - 2]);
+            }
+
+            let bytes = self.rest.as_bytes();
+            let after_separator = core::mem::replace(&mut self.after_separator, false);
+            match bytes.first()?.to_ascii_lowercase() {
+                b'_' => {
+                    self.rest = &self.rest[1..];
+                    self.after_separator = true;
+                }
+                b's' if !after_separator && bytes.get(1).is_some_and(|b| b.eq_ignore_ascii_case(&b'u')) => {
+                    self.rest = &self.rest[2..];
+                }
+                b's' | b'x' | b'z' => {
+                    let end = self.rest.find('_').unwrap_or(self.rest.len());
+                    let name = strip_version(&self.rest[..end]);
+                    self.rest = &self.rest[end..];
+                    return Some(name);
+                }
+                letter if letter.is_ascii_alphabetic() => {
+                    let name = &self.rest[..1];
+                    self.rest = skip_version(&self.rest[1..]);
+                    if letter == b'g' {
+                        self.implied = 1;
+                        continue;
+                    }
+                    return Some(name);
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Splits `rv32`/`rv64` off an ISA string
+fn base(isa: &str) -> Option<(u32, &str)> {
+    let prefix = isa.get(..4)?;
+    let bits = if prefix.eq_ignore_ascii_case("rv32") {
+        32
+    } else if prefix.eq_ignore_ascii_case("rv64") {
+        64
+    } else {
+        return None;
+    };
+
+    Some((bits, &isa[4..]))
+}
+
+/// Skips a `<major>[p<minor>]` version after a single-letter extension
+fn skip_version(rest: &str) -> &str {
+    let digits = |s: &str| s.bytes().take_while(u8::is_ascii_digit).count();
+    let major = digits(rest);
+    if major == 0 {
+        return rest;
+    }
+
+    let rest = &rest[major..];
+    match rest.as_bytes() {
+        [p, d, ..] if p.eq_ignore_ascii_case(&b'p') && d.is_ascii_digit() => &rest[1 + digits(&rest[1..])..],
+        _ => rest,
+    }
+}
+
+/// Drops a trailing `<major>[p<minor>]` version from a multi-letter extension
+fn strip_version(name: &str) -> &str {
+    let trimmed = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if trimmed.len() == name.len() {
+        return name;
+    }
+
+    match trimmed.strip_suffix(['p', 'P']) {
+        Some(major) if major.ends_with(|c: char| c.is_ascii_digit()) => {
+            major.trim_end_matches(|c: char| c.is_ascii_digit())
+        }
+        _ => trimmed,
+    }
+}
+
+/// Represents a RISC-V `/cpus/cpu*` node with specific helper methods
+#[derive(Debug, Clone, Copy)]
+pub struct RiscvHart<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> RiscvHart<'b, 'a> {
+    /// Wraps `cpu` if it is compatible with `riscv`
+    pub fn new(cpu: Cpu<'b, 'a>) -> Option<Self> {
+        Self::from_node(cpu.node)
+    }
+
+    /// Hart owning the `riscv,cpu-intc` node `intc`, e.g. one referenced
+    /// by the `interrupts-extended` of a PLIC or CLINT
+    pub fn from_intc(intc: FdtNode<'b, 'a>) -> Option<Self> {
+        Self::from_node(intc.parent()?)
+    }
+
+    fn from_node(node: FdtNode<'b, 'a>) -> Option<Self> {
+        node.compatible()?.all().any(|c| c == "riscv").then_some(Self { node })
+    }
+
+    /// The CPU node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// Hart ID from `reg`
+    pub fn hart_id(&self) -> Option<u64> {
+        self.node.property("reg").map(|p| read_cells(p.value))
+    }
+
+    /// ISA from `riscv,isa-base` and `riscv,isa-extensions`, or else the
+    /// older `riscv,isa` string
+    pub fn isa(&self) -> Option<RiscvIsa<'a>> {
+        if let Some(list) = self.node.property("riscv,isa-extensions") {
+            let (bits, _) = base(self.node.property("riscv,isa-base")?.as_str()?)?;
+            return Some(RiscvIsa { bits, extensions: Extensions::List(list.value) });
+        }
+
+        RiscvIsa::parse(self.node.property("riscv,isa")?.as_str()?)
+    }
+
+    /// Whether the hart implements extension `name`
+    pub fn has(&self, name: &str) -> bool {
+        self.isa().is_some_and(|isa| isa.has(name))
+    }
+
+    /// `mmu-type`, `None` when absent or `riscv,none`
+    pub fn mmu_type(&self) -> Option<MmuType> {
+        match self.node.property("mmu-type")?.as_str()? {
+            "riscv,sv32" => Some(MmuType::Sv32),
+            "riscv,sv39" => Some(MmuType::Sv39),
+            "riscv,sv48" => Some(MmuType::Sv48),
+            "riscv,sv57" => Some(MmuType::Sv57),
+            _ => None,
+        }
+    }
+
+    /// `riscv,cbom-block-size`: cache block size for Zicbom operations
+    pub fn cbom_block_size(&self) -> Option<u32> {
+        self.node.u32_property("riscv,cbom-block-size")
+    }
+
+    /// `riscv,cboz-block-size`: cache block size for Zicboz operations
+    pub fn cboz_block_size(&self) -> Option<u32> {
+        self.node.u32_property("riscv,cboz-block-size")
+    }
+
+    /// `riscv,cbop-block-size`: cache block size for Zicbop operations
+    pub fn cbop_block_size(&self) -> Option<u32> {
+        self.node.u32_property("riscv,cbop-block-size")
+    }
+
+    /// The hart-local `riscv,cpu-intc` interrupt controller child
+    pub fn interrupt_controller(&self) -> Option<FdtNode<'b, 'a>> {
+        self.node
+            .children()
+            .find(|child| child.compatible().is_some_and(|c| c.all().any(|c| c == "riscv,cpu-intc")))
+    }
+}
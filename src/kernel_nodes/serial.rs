@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Serial console nodes
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/serial/serial.yaml
+
+use crate::node::FdtNode;
+
+/// Parity of a serial line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit
+    None,
+    /// Odd parity
+    Odd,
+    /// Even parity
+    Even,
+}
+
+/// Line settings from the options of `stdout-path`, e.g. `115200n8r`
+///
+/// The format is `<baud>{<parity>{<bits>{<flow>}}}` as parsed by Linux's
+/// `uart_parse_options`: parity is `n`, `o` or `e`, bits are 5 to 8 and
+/// `r` turns on RTS/CTS flow control. Missing fields default to `n8`
+/// without flow control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsoleSpec {
+    /// Baud rate
+    pub baud: u32,
+    /// Parity
+    pub parity: Parity,
+    /// Number of data bits
+    pub data_bits: u8,
+    /// RTS/CTS flow control
+    pub flow_control: bool,
+}
+
+impl ConsoleSpec {
+    /// `baud` with no parity, 8 data bits and no flow control
+    pub fn new(baud: u32) -> Self {
+        Self { baud, parity: Parity::None, data_bits: 8, flow_control: false }
+    }
+
+    /// Parses an options string, `None` if it doesn't start with a baud
+    /// rate or has an unknown parity or data width
+    pub fn parse(options: &str) -> Option<Self> {
+        let digits = options.bytes().take_while(u8::is_ascii_digit).count();
+        let mut spec = Self::new(options[..digits].parse().ok()?);
+        let mut rest = options[digits..].bytes();
+
+        if let Some(parity) = rest.next() {
+            spec.parity = match parity {
+                b'n' => Parity::None,
+                b'o' => Parity::Odd,
+                b'e' => Parity::Even,
+                _ => return None,
+            };
+        }
+        if let Some(bits) = rest.next() {
+            spec.data_bits = match bits {
+                b'5'..=b'8' => bits - b'0',
+                _ => return None,
+            };
+        }
+        spec.flow_control = rest.next() == Some(b'r');
+
+        Some(spec)
+    }
+}
+
+/// UART controllers understood by [`Uart`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UartKind {
+    /// 8250/16550 compatible (`ns16550a`, `ns16550`)
+    Ns16550,
+    /// Synopsys DesignWare APB UART (`snps,dw-apb-uart`), 16550 with
+    /// wider registers
+    DwApb,
+    /// ARM PrimeCell PL011 (`arm,pl011`)
+    Pl011,
+    /// SiFive UART (`sifive,uart0`)
+    Sifive,
+}
+
+/// Everything needed to drive a UART as an early console
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EarlyConsole {
+    /// Controller type
+    pub kind: UartKind,
+    /// CPU physical address of the registers
+    pub base: u64,
+    /// Size of the register window
+    pub size: u64,
+    /// Register stride as a shift
+    pub reg_shift: u32,
+    /// Register access width in bytes
+    pub reg_io_width: u32,
+    /// Input clock, if known
+    pub clock_frequency: Option<u32>,
+    /// Line settings, from the `stdout-path` options or `current-speed`;
+    /// `None` to keep what firmware set up
+    pub spec: Option<ConsoleSpec>,
+}
+
+/// A serial controller node
+#[derive(Debug, Clone, Copy)]
+pub struct Uart<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Uart<'b, 'a> {
+    /// Wraps a serial controller node
+    pub fn new(node: FdtNode<'b, 'a>) -> Self {
+        Self { node }
+    }
+
+    /// The serial controller node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// Controller type, from the first known `compatible`
+    pub fn kind(&self) -> Option<UartKind> {
+        self.node.compatible()?.all().find_map(|c| match c {
+            "ns16550a" | "ns16550" => Some(UartKind::Ns16550),
+            "snps,dw-apb-uart" => Some(UartKind::DwApb),
+            "arm,pl011" => Some(UartKind::Pl011),
+            "sifive,uart0" => Some(UartKind::Sifive),
+            _ => None,
+        })
+    }
+
+    /// First `reg` entry translated to a CPU physical address, and its size
+    pub fn registers(&self) -> Option<(u64, u64)> {
+        let (address, size) = self.node.reg()?.next_raw()?;
+        Some((self.node.translate_address(address)?, size))
+    }
+
+    /// `reg-shift`, 0 if absent
+    pub fn reg_shift(&self) -> u32 {
+        self.node.u32_property("reg-shift").unwrap_or(0)
+    }
+
+    /// `reg-io-width`, 1 if absent
+    pub fn reg_io_width(&self) -> u32 {
+        self.node.u32_property("reg-io-width").unwrap_or(1)
+    }
+
+    /// `clock-frequency` of the input clock
+    pub fn clock_frequency(&self) -> Option<u32> {
+        self.node.u32_property("clock-frequency")
+    }
+
+    /// `current-speed`: baud rate the line is set up for
+    pub fn current_speed(&self) -> Option<u32> {
+        self.node.u32_property("current-speed")
+    }
+
+    /// Early console descriptor, with line settings from `spec` or else
+    /// `current-speed`; `None` for an unknown controller or untranslatable
+    /// registers
+    pub fn early_console(&self, spec: Option<ConsoleSpec>) -> Option<EarlyConsole> {
+        let (base, size) = self.registers()?;
+
+        Some(EarlyConsole {
+            kind: self.kind()?,
+            base,
+            size,
+            reg_shift: self.reg_shift(),
+            reg_io_width: self.reg_io_width(),
+            clock_frequency: self.clock_frequency(),
+            spec: spec.or_else(|| self.current_speed().map(ConsoleSpec::new)),
+        })
+    }
+}
@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Linux kernel thermal zone nodes
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/thermal/thermal-zones.yaml
+
+use crate::node::FdtNode;
+use crate::phandle::PhandleArgs;
+
+/// `THERMAL_NO_LIMIT`: no bound on a cooling state
+const THERMAL_NO_LIMIT: u32 = u32::MAX;
+
+/// What happens when a trip point is crossed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TripType {
+    /// `active`: turn on active cooling, e.g. a fan
+    Active,
+    /// `passive`: throttle the devices of the zone
+    Passive,
+    /// `hot`: notify the system
+    Hot,
+    /// `critical`: shut the system down
+    Critical,
+}
+
+/// A trip point in the `trips` node of a zone
+#[derive(Debug, Clone, Copy)]
+pub struct Trip<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> Trip<'b, 'a> {
+    /// `temperature` in millicelsius
+    pub fn temperature(&self) -> Option<i32> {
+        self.node.u32_property("temperature").map(|t| t as i32)
+    }
+
+    /// `hysteresis` in millicelsius
+    pub fn hysteresis(&self) -> Option<u32> {
+        self.node.u32_property("hysteresis")
+    }
+
+    /// `type`, `None` if absent or unknown
+    pub fn kind(&self) -> Option<TripType> {
+        match self.node.property("type")?.as_str()? {
+            "active" => Some(TripType::Active),
+            "passive" => Some(TripType::Passive),
+            "hot" => Some(TripType::Hot),
+            "critical" => Some(TripType::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// A cooling device bound to a trip point, with the usable state range
+#[derive(Debug, Clone, Copy)]
+pub struct CoolingDevice<'b, 'a> {
+    /// Cooling device node
+    pub node: FdtNode<'b, 'a>,
+    /// Lowest state, `None` for `THERMAL_NO_LIMIT`
+    pub min_state: Option<u32>,
+    /// Highest state, `None` for `THERMAL_NO_LIMIT`
+    pub max_state: Option<u32>,
+}
+
+impl<'b, 'a> From<PhandleArgs<'b, 'a>> for CoolingDevice<'b, 'a> {
+    fn from(spec: PhandleArgs<'b, 'a>) -> Self {
+        let state = |i: usize| spec.args().get(i).copied().filter(|&s| s != THERMAL_NO_LIMIT);
+        Self { node: spec.node, min_state: state(0), max_state: state(1) }
+    }
+}
+
+/// A map in the `cooling-maps` node of a zone
+#[derive(Debug, Clone, Copy)]
+pub struct CoolingMap<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> CoolingMap<'b, 'a> {
+    /// Trip point from `trip`
+    pub fn trip(&self) -> Option<Trip<'b, 'a>> {
+        let phandle = self.node.u32_property("trip")?;
+        self.node.header.find_phandle(phandle).map(|node| Trip { node })
+    }
+
+    /// Cooling devices from `cooling-device`
+    pub fn devices(&self) -> impl Iterator<Item = CoolingDevice<'b, 'a>> + 'b {
+        self.node.phandle_args("cooling-device", "#cooling-cells").map(CoolingDevice::from)
+    }
+
+    /// `contribution`: weight of the devices among those of the zone
+    pub fn contribution(&self) -> Option<u32> {
+        self.node.u32_property("contribution")
+    }
+}
+
+/// A zone under `/thermal-zones`
+#[derive(Debug, Clone, Copy)]
+pub struct ThermalZone<'b, 'a> {
+    /// node
+    pub node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> ThermalZone<'b, 'a> {
+    /// Zone name, the node name
+    pub fn name(&self) -> &'a str {
+        self.node.name
+    }
+
+    /// `polling-delay` in ms, 0 for interrupt driven zones
+    pub fn polling_delay(&self) -> Option<u32> {
+        self.node.u32_property("polling-delay")
+    }
+
+    /// `polling-delay-passive` in ms, used while passive cooling
+    pub fn polling_delay_passive(&self) -> Option<u32> {
+        self.node.u32_property("polling-delay-passive")
+    }
+
+    /// `sustainable-power` in mW
+    pub fn sustainable_power(&self) -> Option<u32> {
+        self.node.u32_property("sustainable-power")
+    }
+
+    /// Sensors from `thermal-sensors`
+    pub fn sensors(&self) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        self.node.phandle_args("thermal-sensors", "#thermal-sensor-cells")
+    }
+
+    /// Trip points, children of `trips`
+    pub fn trips(&self) -> impl Iterator<Item = Trip<'b, 'a>> + 'b {
+        self.child("trips").map(|node| Trip { node })
+    }
+
+    /// Cooling maps, children of `cooling-maps`
+    pub fn cooling_maps(&self) -> impl Iterator<Item = CoolingMap<'b, 'a>> + 'b {
+        self.child("cooling-maps").map(|node| CoolingMap { node })
+    }
+
+    fn child(&self, name: &'b str) -> impl Iterator<Item = FdtNode<'b, 'a>> + 'b {
+        self.node.children().filter(move |child| child.name == name).flat_map(|child| child.children())
+    }
+}
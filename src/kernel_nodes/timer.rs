@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Architected timers: the ARM generic timer and the RISC-V timer
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/timer/arm,arch_timer.yaml
+//! and https://www.kernel.org/doc/Documentation/devicetree/bindings/timer/arm,arch_timer_mmio.yaml
+
+use crate::node::FdtNode;
+use crate::{LinuxFdt, RiscvHart};
+
+/// Names of the ARM arch timer interrupts, in binding order
+const ARCH_TIMER_NAMES: [&str; 5] = ["sec-phys", "phys", "virt", "hyp-phys", "hyp-virt"];
+
+/// Which architected timer a [`Timer`] describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerKind {
+    /// `arm,armv8-timer`
+    Armv8,
+    /// `arm,armv7-timer`
+    Armv7,
+    /// RISC-V `time` CSR with SBI or Sstc timer events
+    Riscv,
+}
+
+/// Interrupts of the ARM arch timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchTimerPpi {
+    /// `sec-phys`: secure physical timer
+    SecurePhys,
+    /// `phys`: non-secure physical timer
+    Phys,
+    /// `virt`: virtual timer
+    Virt,
+    /// `hyp-phys`: hypervisor physical timer
+    HypPhys,
+    /// `hyp-virt`: hypervisor virtual timer
+    HypVirt,
+}
+
+/// A GIC interrupt specifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerIrq {
+    /// PPI rather than SPI
+    pub ppi: bool,
+    /// Interrupt number within its type
+    pub number: u32,
+    /// Trigger type and, for GICv2 PPIs, the CPU mask
+    pub flags: u32,
+}
+
+/// Everything clocksource setup needs from the devicetree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timer {
+    /// Timer type
+    pub kind: TimerKind,
+    /// Counter frequency in Hz: the `clock-frequency` override on ARM, where
+    /// `None` means reading `CNTFRQ`, or `timebase-frequency` on RISC-V
+    pub frequency: Option<u32>,
+    /// ARM interrupts, indexed by [`ArchTimerPpi`]
+    pub ppis: [Option<TimerIrq>; 5],
+    /// The timer keeps running in deep idle states (`always-on`; on RISC-V,
+    /// unless `riscv,timer-cannot-wake-cpu`)
+    pub always_on: bool,
+    /// `arm,no-tick-in-suspend`: the counter stops during system suspend
+    pub no_tick_in_suspend: bool,
+}
+
+impl Timer {
+    /// ARM interrupt `which`
+    pub fn ppi(&self, which: ArchTimerPpi) -> Option<TimerIrq> {
+        self.ppis[which as usize]
+    }
+}
+
+/// A frame of an `arm,armv7-timer-mem`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerFrame {
+    /// `frame-number`
+    pub number: u32,
+    /// CPU physical address of the physical view `CNTBaseN`
+    pub base: u64,
+    /// CPU physical address of the virtual view `CNTEL0BaseN`, if present
+    pub el0_base: Option<u64>,
+    /// Physical timer interrupt
+    pub phys_irq: Option<TimerIrq>,
+    /// Virtual timer interrupt
+    pub virt_irq: Option<TimerIrq>,
+}
+
+/// Represents an `arm,armv7-timer-mem` node with specific helper methods
+#[derive(Debug, Clone, Copy)]
+pub struct TimerMem<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> TimerMem<'b, 'a> {
+    /// The timer node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// CPU physical address of the `CNTCTLBase` frame
+    pub fn control_base(&self) -> Option<u64> {
+        self.node.translate_address(self.node.reg()?.next_raw()?.0)
+    }
+
+    /// `clock-frequency` override
+    pub fn frequency(&self) -> Option<u32> {
+        self.node.u32_property("clock-frequency")
+    }
+
+    /// Available `frame@*` children
+    pub fn frames(&self) -> impl Iterator<Item = TimerFrame> + 'b {
+        self.node.children().filter(|frame| frame.is_available()).filter_map(|frame| {
+            let mut reg = frame.reg()?;
+            let base = frame.translate_address(reg.next_raw()?.0)?;
+            let el0_base = reg.next_raw().and_then(|(address, _)| frame.translate_address(address));
+            let mut irqs = interrupts(frame);
+
+            Some(TimerFrame {
+                number: frame.u32_property("frame-number")?,
+                base,
+                el0_base,
+                phys_irq: irqs.next(),
+                virt_irq: irqs.next(),
+            })
+        })
+    }
+}
+
+impl Timer {
+    /// The ARM generic timer node if there is one, or else the RISC-V timer
+    /// with the `/cpus` timebase when the CPUs are RISC-V harts
+    pub(crate) fn find(fdt: &LinuxFdt<'_>) -> Option<Self> {
+        let arm = fdt.all_nodes().filter(|node| node.is_available()).find_map(|node| {
+            node.compatible()?.all().find_map(|c| match c {
+                "arm,armv8-timer" => Some((node, TimerKind::Armv8)),
+                "arm,armv7-timer" => Some((node, TimerKind::Armv7)),
+                _ => None,
+            })
+        });
+
+        if let Some((node, kind)) = arm {
+            let mut ppis = [None; 5];
+            let named = node.property("interrupt-names").is_some();
+            for (index, irq) in interrupts(node).enumerate() {
+                let slot = match named {
+                    true => node.string_at("interrupt-names", index).and_then(|n| {
+                        ARCH_TIMER_NAMES.iter().position(|&name| name == n)
+                    }),
+                    false => Some(index),
+                };
+                if let Some(slot) = slot.filter(|&slot| slot < ppis.len()) {
+                    ppis[slot] = Some(irq);
+                }
+            }
+
+            return Some(Timer {
+                kind,
+                frequency: node.u32_property("clock-frequency"),
+                ppis,
+                always_on: node.property("always-on").is_some(),
+                no_tick_in_suspend: node.property("arm,no-tick-in-suspend").is_some(),
+            });
+        }
+
+        // timebase-frequency alone doesn't make a RISC-V system
+        if !fdt.cpus().any(|cpu| RiscvHart::new(cpu).is_some()) {
+            return None;
+        }
+
+        let cpus = fdt.find_node("/cpus")?;
+        let frequency = cpus.u32_property("timebase-frequency").or_else(|| {
+            cpus.children().find_map(|cpu| cpu.u32_property("timebase-frequency"))
+        })?;
+        let riscv_timer = fdt.all_nodes().find(|node| {
+            node.compatible().is_some_and(|c| c.all().any(|c| c == "riscv,timer"))
+        });
+
+        Some(Timer {
+            kind: TimerKind::Riscv,
+            frequency: Some(frequency),
+            ppis: [None; 5],
+            always_on: riscv_timer.is_none_or(|timer| timer.property("riscv,timer-cannot-wake-cpu").is_none()),
+            no_tick_in_suspend: false,
+        })
+    }
+}
+
+/// GIC specifiers of the `interrupts` of `node`
+fn interrupts<'a>(node: FdtNode<'_, 'a>) -> impl Iterator<Item = TimerIrq> + 'a {
+    let cells = node.interrupt_domain_cells().filter(|&cells| cells >= 3).unwrap_or(usize::MAX);
+    let value = node.property("interrupts").map(|p| p.value).unwrap_or(&[]);
+
+    value.chunks_exact(cells.saturating_mul(4)).map(|spec| {
+        let cell = |i: usize| u32::from_be_bytes([spec[i * 4], spec[i * 4 + 1], spec[i * 4 + 2], spec[i * 4 + 3]]);
+        TimerIrq { ppi: cell(0) == 1, number: cell(1), flags: cell(2) }
+    })
+}
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Virtio over memory mapped transport
+//!
+//! Reference: https://www.kernel.org/doc/Documentation/devicetree/bindings/virtio/mmio.yaml
+
+use crate::node::FdtNode;
+use crate::phandle::PhandleArgs;
+
+/// Represents a `virtio,mmio` node with specific helper methods
+#[derive(Debug, Clone, Copy)]
+pub struct VirtioMmio<'b, 'a> {
+    pub(crate) node: FdtNode<'b, 'a>,
+}
+
+impl<'b, 'a: 'b> VirtioMmio<'b, 'a> {
+    /// The device node
+    pub fn node(&self) -> FdtNode<'b, 'a> {
+        self.node
+    }
+
+    /// Register window translated to a CPU physical address, and its size
+    pub fn registers(&self) -> Option<(u64, u64)> {
+        let (address, size) = self.node.reg()?.next_raw()?;
+        Some((self.node.translate_address(address)?, size))
+    }
+
+    /// Interrupts with their controller
+    pub fn interrupts(&self) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        self.node.interrupt_specifiers()
+    }
+
+    /// `dma-coherent`: DMA is cache coherent
+    pub fn dma_coherent(&self) -> bool {
+        self.node.property("dma-coherent").is_some()
+    }
+}
@@ -8,21 +8,40 @@
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 mod standard_nodes;
 mod kernel_nodes;
+mod bindings;
 mod error;
 mod parsing;
 mod node;
 mod header;
-mod pretty_print;
+mod mutable;
+mod memory_map;
+mod phandle;
+mod dts;
+#[cfg(feature = "alloc")]
+mod builder;
 
 pub use kernel_nodes::*;
+pub use bindings::*;
 pub use standard_nodes::*;
-pub use error::FdtError;
-pub use node::FdtNode;
+pub use error::{FdtError, ReservedMemoryError};
+pub use node::{FdtNode, MemoryReservation};
+pub use mutable::LinuxFdtMut;
+pub use memory_map::{MemoryKind, MemoryMap, MemoryRange};
+pub use phandle::{PhandleArgs, MAX_PHANDLE_ARGS};
+pub use dts::{DtsWriter, Indent};
+#[cfg(feature = "alloc")]
+pub use dts::{DtsCompiler, DtsError, IncludeResolver};
+#[cfg(feature = "alloc")]
+pub use builder::FdtBuilder;
 use parsing::{FdtData, BigEndianU32, CStr};
 use header::FdtHeader;
-use node::MemoryReservation;
 
 /// A flattened devicetree located somewhere in memory
 ///
@@ -34,8 +53,7 @@ pub struct LinuxFdt<'a> {
 
 impl core::fmt::Debug for LinuxFdt<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        pretty_print::print_node(f, self.root().node, 0)?;
-        Ok(())
+        DtsWriter::new().write(self, f)
     }
 }
 
@@ -109,6 +127,89 @@ impl<'a> LinuxFdt<'a> {
             .map(|node| Dice { node })
     }
 
+    /// Returns every available `simple-framebuffer` node
+    pub fn simple_framebuffers(&self) -> impl Iterator<Item = Framebuffer<'_, 'a>> + '_ {
+        self.all_nodes().filter(|node| node.is_available()).filter_map(Framebuffer::new)
+    }
+
+    /// Returns the `/cpus/cpu*` nodes
+    pub fn cpus(&self) -> impl Iterator<Item = Cpu<'_, 'a>> {
+        self.find_node("/cpus").into_iter().flat_map(|parent| {
+            parent
+                .children()
+                .filter(|node| node.name.split('@').next() == Some("cpu"))
+                .map(move |node| Cpu { parent, node })
+        })
+    }
+
+    /// Returns the PSCI node, matched by its `compatible` like Linux does
+    pub fn psci(&self) -> Option<Psci<'_, 'a>> {
+        self.all_nodes()
+            .find(|node| {
+                node.compatible()
+                    .is_some_and(|c| c.all().any(|c| matches!(c, "arm,psci" | "arm,psci-0.2" | "arm,psci-1.0")))
+            })
+            .map(|node| Psci { node })
+    }
+
+    /// Returns the `/cpus/idle-states` container
+    pub fn idle_states(&self) -> Option<IdleStates<'_, 'a>> {
+        self.find_node("/cpus/idle-states").map(|node| IdleStates { node })
+    }
+
+    /// Returns the architected timer: the ARM generic timer node if there is
+    /// one, or else the RISC-V timer with the `/cpus` timebase if the CPUs
+    /// are RISC-V harts
+    pub fn timer(&self) -> Option<Timer> {
+        Timer::find(self)
+    }
+
+    /// Returns the `arm,armv7-timer-mem` node
+    pub fn timer_mem(&self) -> Option<TimerMem<'_, 'a>> {
+        self.all_nodes()
+            .filter(|node| node.is_available())
+            .find(|node| node.compatible().is_some_and(|c| c.all().any(|c| c == "arm,armv7-timer-mem")))
+            .map(|node| TimerMem { node })
+    }
+
+    /// Returns the available zones under `/thermal-zones`
+    pub fn thermal_zones(&self) -> impl Iterator<Item = ThermalZone<'_, 'a>> + '_ {
+        self.find_node("/thermal-zones")
+            .into_iter()
+            .flat_map(|zones| zones.children())
+            .filter(|zone| zone.is_available())
+            .map(|node| ThermalZone { node })
+    }
+
+    /// Returns the `/firmware` node
+    pub fn firmware(&self) -> Option<Firmware<'_, 'a>> {
+        self.find_node("/firmware").map(|node| Firmware { node })
+    }
+
+    /// Returns the SCPI node, matched by its `compatible` as it is usually
+    /// a child of the root rather than of `/firmware`
+    pub fn scpi(&self) -> Option<Scpi<'_, 'a>> {
+        self.all_nodes()
+            .filter(|node| node.is_available())
+            .find(|node| {
+                node.compatible().is_some_and(|c| c.all().any(|c| matches!(c, "arm,scpi" | "arm,scpi-pre-1.0")))
+            })
+            .map(|node| Scpi { node })
+    }
+
+    /// Returns the `/hypervisor` node
+    pub fn hypervisor(&self) -> Option<Hypervisor<'_, 'a>> {
+        self.find_node("/hypervisor").map(|node| Hypervisor { node })
+    }
+
+    /// Returns every available `virtio,mmio` node
+    pub fn virtio_mmio_devices(&self) -> impl Iterator<Item = VirtioMmio<'_, 'a>> + '_ {
+        self.all_nodes()
+            .filter(|node| node.is_available())
+            .filter(|node| node.compatible().is_some_and(|c| c.all().any(|c| c == "virtio,mmio")))
+            .map(|node| VirtioMmio { node })
+    }
+
     /// Returns interrupt controller node
     pub fn interrupt_controller(&self) -> Option<InterruptController<'_, 'a>> {
         let ic_node = self.all_nodes()
@@ -117,12 +218,12 @@ impl<'a> LinuxFdt<'a> {
     }
 
     /// Return the reserved memory nodes
+    ///
+    /// The node is returned even if it is malformed, see
+    /// [`ReservedMemory::check`] and [`ReservedMemory::invalid_nodes`].
     pub fn linux_reserved_memory(&self) -> Option<ReservedMemory<'_, 'a>>  {
-        let rnode = node::find_node(&mut FdtData::new(self.structs_block()), "/reserved-memory", self, None)
-            .map(|node| ReservedMemory { node })?;
-        // check reserved-memory node is valid
-        rnode.check_root().ok()?;
-        Some(rnode)
+        node::find_node(&mut FdtData::new(self.structs_block()), "/reserved-memory", self, None)
+            .map(|node| ReservedMemory { node })
     }
 
     /// System memory reservations
@@ -137,7 +238,7 @@ impl<'a> LinuxFdt<'a> {
 
             let res = MemoryReservation::from_bytes(&mut stream)?;
 
-            if res.address() as usize == 0 && res.size() == 0 {
+            if res.start() == 0 && res.size() == 0 {
                 done = true;
                 return None;
             }
@@ -146,6 +247,14 @@ impl<'a> LinuxFdt<'a> {
         })
     }
 
+    /// Builds the physical memory map described by the devicetree, keeping
+    /// the ranges in `storage`
+    pub fn memory_map<'s>(&self, storage: &'s mut [MemoryRange]) -> Result<MemoryMap<'s>, FdtError> {
+        let mut map = MemoryMap::new(storage);
+        map.add_fdt(self)?;
+        Ok(map)
+    }
+
     /// Returns the avaiable mem regions
     pub fn mem_nodes(&self) -> impl Iterator<Item = Memory<'_, 'a>> + '_ {
         self.all_nodes()
@@ -183,11 +292,12 @@ impl<'a> LinuxFdt<'a> {
         node.or_else(|| self.aliases()?.resolve_node(path))
     }
 
-    /// Searches for the given `phandle`
+    /// Searches for the given `phandle`, also matching the legacy
+    /// `linux,phandle`
     pub fn find_phandle(&self, phandle: u32) -> Option<node::FdtNode<'_, 'a>> {
         self.all_nodes().find(|n| {
             n.properties()
-                .find(|p| p.name == "phandle")
+                .find(|p| p.name == "phandle" || p.name == "linux,phandle")
                 .and_then(|p| Some(BigEndianU32::from_bytes(p.value)?.get() == phandle))
                 .unwrap_or(false)
         })
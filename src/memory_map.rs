@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Physical memory map
+//!
+//! Combines the memory nodes, `linux,usable-memory-range`, the memory
+//! reservation block and `/reserved-memory` into one sorted list of ranges,
+//! following the order Linux applies them to memblock.
+
+use crate::{Chosen, FdtError, LinuxFdt};
+
+/// What a range of physical memory may be used for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemoryKind {
+    /// Memory free for the OS to use
+    #[default]
+    Usable,
+    /// Reserved, but still part of the linear mapping
+    Reserved,
+    /// Memory that must not be mapped (`no-map`)
+    NoMap,
+}
+
+/// A range of physical memory in a [`MemoryMap`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryRange {
+    /// Physical start address
+    pub start: u64,
+    /// Size of the range in bytes
+    pub size: u64,
+    /// What the range may be used for
+    pub kind: MemoryKind,
+}
+
+impl MemoryRange {
+    /// Physical end address (exclusive), saturated at `u64::MAX`
+    pub fn end(&self) -> u64 {
+        self.start.saturating_add(self.size)
+    }
+
+    /// Physical address range
+    pub fn range(&self) -> core::ops::Range<u64> {
+        self.start..self.end()
+    }
+
+    fn new(start: u64, end: u64, kind: MemoryKind) -> Self {
+        Self { start, size: end - start, kind }
+    }
+}
+
+/// Sorted, merged and non-overlapping map of physical memory
+///
+/// The ranges are kept in storage supplied by the caller, so building a map
+/// needs no allocator. Where ranges of different kinds overlap the stronger
+/// one wins, `NoMap` over `Reserved` over `Usable`.
+///
+/// [`MemoryMap::add_fdt`] applies a devicetree the way Linux sets up memblock
+/// by the end of `early_init_fdt_scan_reserved_mem`:
+///
+/// 1. memory nodes (`linux,usable-memory` in place of `reg` when present)
+/// 2. `linux,usable-memory-range`: the first range caps memory, the others
+///    are added to it
+/// 3. `no-map` regions of `/reserved-memory`, only where they cover memory
+/// 4. the other `/reserved-memory` regions, `linux,elfcorehdr` and the
+///    memory reservation block
+///
+/// Dynamically allocated `/reserved-memory` regions are not part of the map.
+#[derive(Debug)]
+pub struct MemoryMap<'s> {
+    storage: &'s mut [MemoryRange],
+    len: usize,
+}
+
+impl<'s> MemoryMap<'s> {
+    /// Creates an empty map keeping its ranges in `storage`
+    pub fn new(storage: &'s mut [MemoryRange]) -> Self {
+        Self { storage, len: 0 }
+    }
+
+    /// All ranges in address order
+    pub fn ranges(&self) -> &[MemoryRange] {
+        &self.storage[..self.len]
+    }
+
+    /// Ranges of usable memory
+    pub fn usable(&self) -> impl Iterator<Item = MemoryRange> + '_ {
+        self.of_kind(MemoryKind::Usable)
+    }
+
+    /// Reserved ranges
+    pub fn reserved(&self) -> impl Iterator<Item = MemoryRange> + '_ {
+        self.of_kind(MemoryKind::Reserved)
+    }
+
+    /// Ranges that must not be mapped
+    pub fn no_map(&self) -> impl Iterator<Item = MemoryRange> + '_ {
+        self.of_kind(MemoryKind::NoMap)
+    }
+
+    /// Kind of the memory at `address`, `None` if it is not in the map
+    pub fn kind_at(&self, address: u64) -> Option<MemoryKind> {
+        self.ranges().iter().find(|r| r.range().contains(&address)).map(|r| r.kind)
+    }
+
+    /// Adds the memory described by `fdt`, see [`MemoryMap`] for the order
+    pub fn add_fdt(&mut self, fdt: &LinuxFdt<'_>) -> Result<(), FdtError> {
+        for memory in fdt.mem_nodes() {
+            let Some(mut regions) = memory.regions() else { continue };
+            while let Some((start, size)) = regions.next_raw() {
+                self.add_memory(start, size)?;
+            }
+        }
+
+        // `/chosen` is optional here, unlike in `LinuxFdt::chosen`
+        let chosen = fdt.find_node("/chosen").map(|node| Chosen { node });
+
+        if let Some(mut usable) = chosen.and_then(|chosen| chosen.usable_mem_region()) {
+            if let Some((start, size)) = usable.next_raw() {
+                self.cap(start, size);
+            }
+            while let Some((start, size)) = usable.next_raw() {
+                self.add_memory(start, size)?;
+            }
+        }
+
+        if let Some(reserved_memory) = fdt.linux_reserved_memory() {
+            for pass_no_map in [true, false] {
+                for node in reserved_memory.valid_reserved_nodes().filter(|n| n.nomap() == pass_no_map) {
+                    let Ok(mut regions) = node.regions() else { continue };
+                    while let Some((start, size)) = regions.next_raw() {
+                        match pass_no_map {
+                            true => self.mark_no_map(start, size)?,
+                            false => self.reserve(start, size)?,
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(elfcorehdr) = chosen.and_then(|chosen| chosen.elfcorehdr()) {
+            self.reserve(elfcorehdr.start, elfcorehdr.end - elfcorehdr.start)?;
+        }
+
+        for rsv in fdt.sys_memory_reservations() {
+            self.reserve(rsv.start(), rsv.size())?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds usable memory, like `memblock_add`
+    ///
+    /// Parts of the range already in the map keep their kind.
+    pub fn add_memory(&mut self, start: u64, size: u64) -> Result<(), FdtError> {
+        self.paint(start, size, MemoryKind::Usable, |kind| kind.is_none())
+    }
+
+    /// Reserves a range, like `memblock_reserve`
+    ///
+    /// The range doesn't have to be memory; parts of it already marked
+    /// `NoMap` stay that way.
+    pub fn reserve(&mut self, start: u64, size: u64) -> Result<(), FdtError> {
+        self.paint(start, size, MemoryKind::Reserved, |kind| kind != Some(MemoryKind::NoMap))
+    }
+
+    /// Marks a range as not to be mapped, like `memblock_mark_nomap`
+    ///
+    /// Only the parts of the range already in the map are marked.
+    pub fn mark_no_map(&mut self, start: u64, size: u64) -> Result<(), FdtError> {
+        self.paint(start, size, MemoryKind::NoMap, |kind| kind.is_some())
+    }
+
+    /// Drops everything outside `start..start + size`, like
+    /// `memblock_cap_memory_range`
+    ///
+    /// A zero `size` leaves the map unchanged.
+    pub fn cap(&mut self, start: u64, size: u64) {
+        if size == 0 {
+            return;
+        }
+
+        let end = start.saturating_add(size);
+        let mut kept = 0;
+        for i in 0..self.len {
+            let r = self.storage[i];
+            let (lo, hi) = (r.start.max(start), r.end().min(end));
+            if lo < hi {
+                self.storage[kept] = MemoryRange::new(lo, hi, r.kind);
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+
+    fn of_kind(&self, kind: MemoryKind) -> impl Iterator<Item = MemoryRange> + '_ {
+        self.ranges().iter().copied().filter(move |r| r.kind == kind)
+    }
+
+    /// Sets every part of `start..start + size` whose current kind (`None`
+    /// for holes) passes `over` to `kind`
+    ///
+    /// The map is left untouched if the storage can't hold the result.
+    fn paint(
+        &mut self,
+        start: u64,
+        size: u64,
+        kind: MemoryKind,
+        over: impl Fn(Option<MemoryKind>) -> bool,
+    ) -> Result<(), FdtError> {
+        let end = start.saturating_add(size);
+        let first = self.ranges().iter().position(|r| r.end() > start).unwrap_or(self.len);
+
+        // count the ranges to be inserted before touching anything
+        let mut extra = 0;
+        self.walk(first, start, end, |r, pos, seg_end| match r {
+            Some(r) if r.kind != kind && over(Some(r.kind)) => {
+                extra += usize::from(r.start < pos) + usize::from(seg_end < r.end());
+            }
+            None if over(None) => extra += 1,
+            _ => {}
+        });
+        if self.len + extra > self.storage.len() {
+            return Err(FdtError::NoSpace);
+        }
+
+        let mut pos = start;
+        let mut i = first;
+        while pos < end {
+            if i < self.len && self.storage[i].start <= pos {
+                let r = self.storage[i];
+                let seg_end = r.end().min(end);
+                if r.kind != kind && over(Some(r.kind)) {
+                    let mut at = i;
+                    if r.start < pos {
+                        self.storage[i] = MemoryRange::new(r.start, pos, r.kind);
+                        at += 1;
+                        self.insert(at, MemoryRange::new(pos, seg_end, kind));
+                    } else {
+                        self.storage[at] = MemoryRange::new(pos, seg_end, kind);
+                    }
+                    if seg_end < r.end() {
+                        self.insert(at + 1, MemoryRange::new(seg_end, r.end(), r.kind));
+                    }
+                    i = at + 1;
+                } else {
+                    i += 1;
+                }
+                pos = seg_end;
+            } else {
+                let gap_end = if i < self.len { self.storage[i].start.min(end) } else { end };
+                if over(None) {
+                    self.insert(i, MemoryRange::new(pos, gap_end, kind));
+                    i += 1;
+                }
+                pos = gap_end;
+            }
+        }
+
+        self.merge();
+        Ok(())
+    }
+
+    /// Calls `f` for each piece of `start..end`, with the range covering it
+    /// or `None` for a hole, and the start and end of the piece
+    fn walk(&self, mut i: usize, start: u64, end: u64, mut f: impl FnMut(Option<MemoryRange>, u64, u64)) {
+        let mut pos = start;
+        while pos < end {
+            if i < self.len && self.storage[i].start <= pos {
+                let seg_end = self.storage[i].end().min(end);
+                f(Some(self.storage[i]), pos, seg_end);
+                i += 1;
+                pos = seg_end;
+            } else {
+                let gap_end = if i < self.len { self.storage[i].start.min(end) } else { end };
+                f(None, pos, gap_end);
+                pos = gap_end;
+            }
+        }
+    }
+
+    fn insert(&mut self, index: usize, range: MemoryRange) {
+        self.storage.copy_within(index..self.len, index + 1);
+        self.storage[index] = range;
+        self.len += 1;
+    }
+
+    /// Joins neighbouring ranges of the same kind
+    fn merge(&mut self) {
+        let mut kept = 0;
+        for i in 0..self.len {
+            let r = self.storage[i];
+            if kept > 0 {
+                let last = &mut self.storage[kept - 1];
+                if last.kind == r.kind && last.end() == r.start {
+                    last.size += r.size;
+                    continue;
+                }
+            }
+            self.storage[kept] = r;
+            kept += 1;
+        }
+        self.len = kept;
+    }
+}
@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! In-place editing of a flattened devicetree
+
+use crate::{
+    header::{FdtHeader, FDT_HEADER_SIZE},
+    node::MemoryReservation,
+    parsing::{BigEndianU32, FdtData},
+    FdtError, LinuxFdt,
+};
+
+/// Size of one memory reservation block entry
+const MEM_RSV_ENTRY_SIZE: usize = 16;
+
+/// A flattened devicetree that can be modified in place
+///
+/// The buffer may be larger than the devicetree it holds: the spare room
+/// past `totalsize` is what the blob grows into, the same way libfdt's
+/// read-write functions work on an opened-up tree.
+pub struct LinuxFdtMut<'a> {
+    data: &'a mut [u8],
+}
+
+impl<'a> LinuxFdtMut<'a> {
+    /// Wraps a buffer holding a devicetree
+    ///
+    /// The blob has to be at least version 17 with its blocks in the standard
+    /// order (memory reservations, structure, strings).
+    pub fn new(data: &'a mut [u8]) -> Result<Self, FdtError> {
+        let header = FdtHeader::from_bytes(&mut FdtData::new(data)).ok_or(FdtError::BufferTooSmall)?;
+
+        if !header.valid_magic() {
+            return Err(FdtError::BadMagic);
+        } else if data.len() < header.totalsize.get() as usize {
+            return Err(FdtError::BufferTooSmall);
+        } else if header.version.get() < 17 {
+            return Err(FdtError::BadVersion);
+        }
+
+        let this = Self { data };
+        let rsv_end = header.off_mem_rsvmap.get() as usize + (this.num_mem_rsv() + 1) * MEM_RSV_ENTRY_SIZE;
+        let struct_range = header.struct_range();
+        let strings_range = header.strings_range();
+        if (header.off_mem_rsvmap.get() as usize) < FDT_HEADER_SIZE
+            || rsv_end > struct_range.start
+            || struct_range.end > strings_range.start
+            || strings_range.end > header.totalsize.get() as usize
+        {
+            return Err(FdtError::BadLayout);
+        }
+
+        Ok(this)
+    }
+
+    /// Read-only view of the devicetree
+    pub fn as_fdt(&self) -> LinuxFdt<'_> {
+        LinuxFdt::new(self.data).expect("header validated on creation")
+    }
+
+    /// Total size of the devicetree in bytes
+    pub fn total_size(&self) -> usize {
+        self.header().totalsize.get() as usize
+    }
+
+    /// Number of entries in the memory reservation block
+    pub fn num_mem_rsv(&self) -> usize {
+        self.as_fdt().sys_memory_reservations().count()
+    }
+
+    /// Appends `start..start + size` to the memory reservation block
+    ///
+    /// A zero `size` is refused with [`FdtError::BadValue`], as readers take
+    /// such an entry for the end of the block.
+    pub fn add_mem_rsv(&mut self, start: u64, size: u64) -> Result<(), FdtError> {
+        if size == 0 {
+            return Err(FdtError::BadValue);
+        }
+
+        let offset = self.mem_rsv_offset(self.num_mem_rsv());
+        self.splice(offset, 0, MEM_RSV_ENTRY_SIZE)?;
+
+        let entry = &mut self.data[offset..offset + MEM_RSV_ENTRY_SIZE];
+        entry[..8].copy_from_slice(&start.to_be_bytes());
+        entry[8..].copy_from_slice(&size.to_be_bytes());
+
+        Ok(())
+    }
+
+    /// Removes the `index`th entry of the memory reservation block, returning it
+    pub fn del_mem_rsv(&mut self, index: usize) -> Result<MemoryReservation, FdtError> {
+        if index >= self.num_mem_rsv() {
+            return Err(FdtError::NotFound);
+        }
+
+        let offset = self.mem_rsv_offset(index);
+        let rsv = MemoryReservation::from_bytes(&mut FdtData::new(&self.data[offset..]))
+            .ok_or(FdtError::BufferTooSmall)?;
+        self.splice(offset, MEM_RSV_ENTRY_SIZE, 0)?;
+
+        Ok(rsv)
+    }
+
+    fn header(&self) -> FdtHeader {
+        FdtHeader::from_bytes(&mut FdtData::new(self.data)).expect("header validated on creation")
+    }
+
+    fn mem_rsv_offset(&self, index: usize) -> usize {
+        self.header().off_mem_rsvmap.get() as usize + index * MEM_RSV_ENTRY_SIZE
+    }
+
+    /// Replaces `old_len` bytes at `offset`, which must lie in the memory
+    /// reservation block, with `new_len` bytes, moving the structure and
+    /// strings blocks along
+    fn splice(&mut self, offset: usize, old_len: usize, new_len: usize) -> Result<(), FdtError> {
+        let mut header = self.header();
+        let total = header.totalsize.get() as usize;
+        let new_total = total + new_len - old_len;
+        if new_total > self.data.len() {
+            return Err(FdtError::NoSpace);
+        }
+
+        self.data.copy_within(offset + old_len..total, offset + new_len);
+        if new_total < total {
+            self.data[new_total..total].fill(0);
+        }
+
+        let shift = |field: BigEndianU32| BigEndianU32::new((field.get() as usize + new_len - old_len) as u32);
+        header.off_dt_struct = shift(header.off_dt_struct);
+        header.off_dt_strings = shift(header.off_dt_strings);
+        header.totalsize = BigEndianU32::new(new_total as u32);
+        header.store(self.data);
+
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for LinuxFdtMut<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.as_fdt(), f)
+    }
+}
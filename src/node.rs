@@ -4,16 +4,20 @@
 // See LICENSE for license details.
 
 use crate::{
+    kernel_nodes::reserved_memory::ReservedMemoryNode,
     parsing::{BigEndianU32, BigEndianU64, CStr, FdtData},
     standard_nodes::{Compatible, RegIter},
     LinuxFdt,
 };
 
-const FDT_BEGIN_NODE: u32 = 1;
-const FDT_END_NODE: u32 = 2;
-const FDT_PROP: u32 = 3;
+pub(crate) const FDT_BEGIN_NODE: u32 = 1;
+pub(crate) const FDT_END_NODE: u32 = 2;
+pub(crate) const FDT_PROP: u32 = 3;
 pub(crate) const FDT_NOP: u32 = 4;
-const FDT_END: u32 = 5;
+pub(crate) const FDT_END: u32 = 9;
+
+/// Maximum nesting depth supported when walking the tree
+pub(crate) const MAX_DEPTH: usize = 64;
 
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
@@ -41,6 +45,15 @@ pub struct FdtNode<'b, 'a> {
     parent_props: Option<&'a [u8]>,
 }
 
+impl PartialEq for FdtNode<'_, '_> {
+    /// Two nodes are equal if they refer to the same node of the same devicetree
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.props, other.props)
+    }
+}
+
+impl Eq for FdtNode<'_, '_> {}
+
 impl<'b, 'a: 'b> FdtNode<'b, 'a> {
     fn new(
         name: &'a str,
@@ -227,6 +240,71 @@ impl<'b, 'a: 'b> FdtNode<'b, 'a> {
             .and_then(|p| self.header.find_phandle(BigEndianU32::from_bytes(p.value)?.get()))
     }
 
+    /// Parent node, `None` for the root
+    pub fn parent(self) -> Option<FdtNode<'b, 'a>> {
+        let root = self.header.find_node("/")?;
+        let mut stack = [root; MAX_DEPTH];
+        let depth = ancestry(root, self, &mut stack)?;
+        depth.checked_sub(2).map(|i| stack[i])
+    }
+
+    /// Translates `address`, in the address space of this node's `reg`, to a
+    /// CPU physical address through the `ranges` of every bus above the node
+    ///
+    /// Returns `None` if a bus on the way has no `ranges` or none of its
+    /// ranges covers the address. Only the low 64 bits of wider bus
+    /// addresses are compared.
+    pub fn translate_address(self, address: u64) -> Option<u64> {
+        let root = self.header.find_node("/")?;
+        let mut stack = [root; MAX_DEPTH];
+        let depth = ancestry(root, self, &mut stack)?;
+
+        let mut address = address;
+        for i in (1..depth.saturating_sub(1)).rev() {
+            let (bus, parent) = (stack[i], stack[i - 1]);
+            let ranges = bus.property("ranges")?.value;
+            if ranges.is_empty() {
+                continue;
+            }
+
+            let child_cells = bus.cell_sizes().address_cells;
+            let size_cells = bus.cell_sizes().size_cells;
+            let parent_cells = parent.cell_sizes().address_cells;
+            let entry = (child_cells + parent_cells + size_cells) * 4;
+            if entry == 0 {
+                return None;
+            }
+
+            address = ranges.chunks_exact(entry).find_map(|range| {
+                let (child, rest) = range.split_at(child_cells * 4);
+                let (parent, size) = rest.split_at(parent_cells * 4);
+                let (child, parent, size) = (read_cells(child), read_cells(parent), read_cells(size));
+                let offset = address.checked_sub(child)?;
+                (offset < size).then(|| parent + offset)
+            })?;
+        }
+
+        Some(address)
+    }
+
+    /// Reserved memory regions referenced by the `memory-region` property,
+    /// with their index in it, which `memory-region-names` refers to
+    ///
+    /// Phandles that don't resolve are skipped.
+    pub fn memory_regions(self) -> impl Iterator<Item = (usize, ReservedMemoryNode<'b, 'a>)> + 'b {
+        let phandles = self.property("memory-region").map(|p| p.value).unwrap_or(&[]);
+        phandles.chunks_exact(4).enumerate().filter_map(move |(index, ph)| {
+            let node = self.header.find_phandle(BigEndianU32::from_bytes(ph)?.get())?;
+            Some((index, ReservedMemoryNode { node }))
+        })
+    }
+
+    /// Reserved memory region listed as `name` in `memory-region-names`
+    pub fn memory_region(self, name: &str) -> Option<ReservedMemoryNode<'b, 'a>> {
+        let index = self.string_index("memory-region-names", name)?;
+        self.memory_regions().find(|&(i, _)| i == index).map(|(_, region)| region)
+    }
+
     /// `#interrupt-cells` property
     pub fn interrupt_cells(self) -> Option<usize> {
         let mut interrupt_cells = None;
@@ -262,6 +340,52 @@ impl<'b, 'a: 'b> FdtNode<'b, 'a> {
         interrupt
     }
 
+    /// First cell of property `name`, like `of_property_read_u32`
+    pub(crate) fn u32_property(self, name: &str) -> Option<u32> {
+        BigEndianU32::from_bytes(self.property(name)?.value).map(|v| v.get())
+    }
+
+    /// Strings of the string list property `list`
+    pub(crate) fn strings(self, list: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let names = self.property(list).map(|p| p.value).unwrap_or(&[]);
+        let names = names.strip_suffix(&[0]).unwrap_or(names);
+        names.split(|&b| b == 0).filter(move |_| !names.is_empty()).map(|n| core::str::from_utf8(n).unwrap_or(""))
+    }
+
+    /// Position of `name` in the string list property `list`
+    pub(crate) fn string_index(self, list: &str, name: &str) -> Option<usize> {
+        let names = self.property(list)?.value;
+        let names = names.strip_suffix(&[0]).unwrap_or(names);
+        names.split(|&b| b == 0).position(|n| n == name.as_bytes())
+    }
+
+    /// String at `index` of the string list property `list`
+    pub(crate) fn string_at(self, list: &str, index: usize) -> Option<&'a str> {
+        let names = self.property(list)?.value;
+        let names = names.strip_suffix(&[0]).unwrap_or(names);
+        core::str::from_utf8(names.split(|&b| b == 0).nth(index)?).ok()
+    }
+
+    /// Interrupt controller (or nexus) the node's `interrupts` belong to,
+    /// following inherited `interrupt-parent`s like Linux's
+    /// `of_irq_find_parent`
+    pub(crate) fn interrupt_domain(self) -> Option<FdtNode<'b, 'a>> {
+        let mut node = self;
+        for _ in 0..MAX_DEPTH {
+            node = node.interrupt_parent().or_else(|| node.parent())?;
+            if node.interrupt_cells().is_some() {
+                return Some(node);
+            }
+        }
+
+        None
+    }
+
+    /// `#interrupt-cells` of [`FdtNode::interrupt_domain`]
+    pub(crate) fn interrupt_domain_cells(self) -> Option<usize> {
+        self.interrupt_domain()?.interrupt_cells()
+    }
+
     pub(crate) fn parent_cell_sizes(self) -> CellSizes {
         let mut cell_sizes = CellSizes::default();
 
@@ -386,6 +510,30 @@ pub(crate) fn find_node<'b, 'a: 'b>(
     None
 }
 
+/// Walks down from `root` looking for `target`, recording every node on the
+/// way (both ends included) in `stack`. Returns the number of recorded nodes.
+pub(crate) fn ancestry<'b, 'a: 'b>(
+    root: FdtNode<'b, 'a>,
+    target: FdtNode<'b, 'a>,
+    stack: &mut [FdtNode<'b, 'a>],
+) -> Option<usize> {
+    fn walk<'b, 'a: 'b>(
+        node: FdtNode<'b, 'a>,
+        target: FdtNode<'b, 'a>,
+        stack: &mut [FdtNode<'b, 'a>],
+        depth: usize,
+    ) -> Option<usize> {
+        *stack.get_mut(depth)? = node;
+        if node == target {
+            return Some(depth + 1);
+        }
+
+        node.children().find_map(|child| walk(child, target, stack, depth + 1))
+    }
+
+    walk(root, target, stack, 0)
+}
+
 // FIXME: this probably needs refactored
 pub(crate) fn all_nodes<'b, 'a: 'b>(header: &'b LinuxFdt<'a>) -> impl Iterator<Item = FdtNode<'b, 'a>> {
     let mut stream = FdtData::new(header.structs_block());
@@ -527,7 +675,7 @@ impl<'a> NodeProperty<'a> {
 /// Semantics: These ranges are reserved for firmware, secure world, device buffers,
 /// etc. The OS should remove them from available memory early during memory
 /// initialization (e.g., memblock_reserve in Linux).
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C)]
 pub struct MemoryReservation {
     pub(crate) address: BigEndianU64,
@@ -535,14 +683,37 @@ pub struct MemoryReservation {
 }
 
 impl MemoryReservation {
+    /// Creates a reservation of `size` bytes starting at `start`
+    pub fn new(start: u64, size: u64) -> Self {
+        Self { address: BigEndianU64::new(start), size: BigEndianU64::new(size) }
+    }
+
     /// Pointer representing the memory reservation address
+    ///
+    /// Truncated to the platform pointer width, prefer [`MemoryReservation::start`]
     pub fn address(&self) -> *const u8 {
         self.address.get() as usize as *const u8
     }
 
+    /// Physical start address of the reservation
+    pub fn start(&self) -> u64 {
+        self.address.get()
+    }
+
+    /// Physical end address (exclusive) of the reservation, saturated at
+    /// `u64::MAX`
+    pub fn end(&self) -> u64 {
+        self.start().saturating_add(self.size())
+    }
+
     /// Size of the memory reservation
-    pub fn size(&self) -> usize {
-        self.size.get() as usize
+    pub fn size(&self) -> u64 {
+        self.size.get()
+    }
+
+    /// Reserved physical address range
+    pub fn range(&self) -> core::ops::Range<u64> {
+        self.start()..self.end()
     }
 
     pub(crate) fn from_bytes(bytes: &mut FdtData<'_>) -> Option<Self> {
@@ -553,6 +724,17 @@ impl MemoryReservation {
     }
 }
 
+impl From<MemoryReservation> for core::ops::Range<u64> {
+    fn from(rsv: MemoryReservation) -> Self {
+        rsv.range()
+    }
+}
+
+/// Reads big-endian cells as a number, keeping the low 64 bits
+pub(crate) fn read_cells(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |n, &b| (n << 8) | b as u64)
+}
+
 fn skip_4_aligned(stream: &mut FdtData<'_>, len: usize) {
     stream.skip((len + 3) & !0x3);
 }
@@ -28,6 +28,10 @@ impl<'a> CStr<'a> {
 pub struct BigEndianU32(u32);
 
 impl BigEndianU32 {
+    pub(crate) fn new(value: u32) -> Self {
+        BigEndianU32(value)
+    }
+
     pub fn get(self) -> u32 {
         self.0
     }
@@ -37,11 +41,15 @@ impl BigEndianU32 {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct BigEndianU64(u64);
 
 impl BigEndianU64 {
+    pub(crate) fn new(value: u64) -> Self {
+        BigEndianU64(value)
+    }
+
     pub fn get(&self) -> u64 {
         self.0
     }
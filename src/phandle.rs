@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2025 WeiKang Guo <guoweikang.kernel@gmail.com
+// Copyright (C) 2025 KylinSoft Co., Ltd. <https://www.kylinos.cn/>
+// See LICENSE for license details.
+
+//! Phandle lists with arguments, like `clocks = <&clk 1>, <&osc>`
+
+use crate::node::{FdtNode, NodeProperty, MAX_DEPTH};
+use crate::parsing::BigEndianU32;
+
+/// Most argument cells a single phandle list entry may have
+pub const MAX_PHANDLE_ARGS: usize = 16;
+
+/// One entry of a phandle list: the referenced node and its argument cells
+#[derive(Debug, Clone, Copy)]
+pub struct PhandleArgs<'b, 'a> {
+    /// Referenced node
+    pub node: FdtNode<'b, 'a>,
+    /// Index of the entry in the list
+    pub index: usize,
+    args: [u32; MAX_PHANDLE_ARGS],
+    count: usize,
+}
+
+impl<'b, 'a: 'b> PhandleArgs<'b, 'a> {
+    /// Argument cells following the phandle
+    pub fn args(&self) -> &[u32] {
+        &self.args[..self.count]
+    }
+}
+
+impl<'b, 'a: 'b> FdtNode<'b, 'a> {
+    /// Walks the phandle list `list`, taking the number of arguments of each
+    /// entry from the `cells` property of the referenced node, like
+    /// `of_parse_phandle_with_args`
+    ///
+    /// Empty entries (phandle 0) are skipped but still counted in
+    /// [`PhandleArgs::index`]; iteration stops at the first entry that can't
+    /// be decoded.
+    pub fn phandle_args(self, list: &str, cells: &'b str) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        raw_list(self, self.property(list), move |target| target.property(cells)?.as_usize())
+    }
+
+    /// Like [`FdtNode::phandle_args`], but a referenced node without a
+    /// `cells` property takes no arguments, like
+    /// `of_parse_phandle_with_optional_args`
+    pub fn phandle_args_optional(self, list: &str, cells: &'b str) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        raw_list(self, self.property(list), move |target| match target.property(cells) {
+            Some(cells) => cells.as_usize(),
+            None => Some(0),
+        })
+    }
+
+    /// Walks the phandle list `list` whose entries all have `count`
+    /// arguments, like `of_parse_phandle_with_fixed_args`
+    pub fn phandle_args_fixed(self, list: &str, count: usize) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        raw_list(self, self.property(list), move |_| Some(count))
+    }
+
+    /// Entry of `list` named `name` in the string list `names`, e.g.
+    /// `("resets", "#reset-cells", "reset-names", "bus")`
+    pub fn phandle_args_by_name(
+        self,
+        list: &str,
+        cells: &'b str,
+        names: &str,
+        name: &str,
+    ) -> Option<PhandleArgs<'b, 'a>> {
+        let index = self.string_index(names, name)?;
+        self.phandle_args(list, cells).find(|args| args.index == index)
+    }
+
+    /// Like [`FdtNode::phandle_args`] with `#<stem>-cells`, but entries
+    /// pointing at a nexus node are followed through its `<stem>-map`,
+    /// honouring `<stem>-map-mask` and `<stem>-map-pass-thru`, like
+    /// `of_parse_phandle_with_args_map`
+    ///
+    /// Entries that match no map entry end the iteration.
+    pub fn phandle_args_map(self, list: &str, stem: &'b str) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        self.phandle_args_map_of(self.property(list), stem)
+    }
+
+    /// Interrupts with their controller: `interrupts-extended`, or else
+    /// `interrupts` in the inherited interrupt parent's domain
+    ///
+    /// `interrupt-map` nexus nodes are not translated through.
+    pub fn interrupt_specifiers(self) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        let extended = self.property("interrupts-extended");
+        let domain = extended.is_none().then(|| self.interrupt_domain()).flatten();
+        let cells = domain.and_then(|domain| domain.interrupt_cells()).unwrap_or(0);
+        // a cell count too large to size a chunk yields nothing
+        let (value, stride) = match (domain, cells.checked_mul(4)) {
+            (Some(_), Some(stride)) if stride > 0 => {
+                (self.property("interrupts").map(|p| p.value).unwrap_or(&[]), stride)
+            }
+            _ => (&[][..], 4),
+        };
+
+        let plain = domain.into_iter().flat_map(move |domain| {
+            value.chunks_exact(stride).enumerate().filter_map(move |(index, spec)| {
+                let (args, count) = read_args(spec, cells)?;
+                Some(PhandleArgs { node: domain, index, args, count })
+            })
+        });
+
+        raw_list(self, extended, |target| target.interrupt_cells()).chain(plain)
+    }
+
+    pub(crate) fn phandle_args_map_of(
+        self,
+        list: Option<NodeProperty<'a>>,
+        stem: &'b str,
+    ) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+        raw_list(self, list, move |target| property_parts(target, &["#", stem, "-cells"])?.as_usize())
+            .map_while(move |args| resolve_map(args, stem))
+    }
+}
+
+/// Decodes a phandle list whose entry sizes come from `cells`
+fn raw_list<'b, 'a: 'b>(
+    node: FdtNode<'b, 'a>,
+    list: Option<NodeProperty<'a>>,
+    cells: impl Fn(FdtNode<'b, 'a>) -> Option<usize> + 'b,
+) -> impl Iterator<Item = PhandleArgs<'b, 'a>> + 'b {
+    let mut value = list.map(|p| p.value).unwrap_or(&[]);
+    let mut index = 0;
+
+    core::iter::from_fn(move || loop {
+        let phandle = BigEndianU32::from_bytes(value)?.get();
+        value = &value[4..];
+        index += 1;
+        if phandle == 0 {
+            continue;
+        }
+
+        let target = node.header.find_phandle(phandle)?;
+        let (args, count) = read_args(value, cells(target)?)?;
+        value = &value[count * 4..];
+
+        return Some(PhandleArgs { node: target, index: index - 1, args, count });
+    })
+}
+
+fn read_args(value: &[u8], count: usize) -> Option<([u32; MAX_PHANDLE_ARGS], usize)> {
+    if count > MAX_PHANDLE_ARGS || value.len() < count * 4 {
+        return None;
+    }
+
+    let mut args = [0; MAX_PHANDLE_ARGS];
+    for (arg, cell) in args.iter_mut().zip(value.chunks_exact(4)).take(count) {
+        *arg = u32::from_be_bytes([cell[0], cell[1], cell[2], cell[3]]);
+    }
+    Some((args, count))
+}
+
+/// Property whose name is `parts` joined together
+pub(crate) fn property_parts<'a>(node: FdtNode<'_, 'a>, parts: &[&str]) -> Option<NodeProperty<'a>> {
+    node.properties().find(|p| {
+        parts.iter().try_fold(p.name, |name, part| name.strip_prefix(part)) == Some("")
+    })
+}
+
+/// Follows `args` through nexus nodes until it reaches a real provider
+fn resolve_map<'b, 'a: 'b>(mut args: PhandleArgs<'b, 'a>, stem: &str) -> Option<PhandleArgs<'b, 'a>> {
+    for _ in 0..MAX_DEPTH {
+        let Some(map) = property_parts(args.node, &[stem, "-map"]) else {
+            return Some(args);
+        };
+
+        let nexus = args.node;
+        let cells = |suffix: &str, count: usize| {
+            property_parts(nexus, &[stem, suffix]).and_then(|p| read_args(p.value, count)).map(|(v, _)| v)
+        };
+        let mask = cells("-map-mask", args.count).unwrap_or([u32::MAX; MAX_PHANDLE_ARGS]);
+
+        let mut entries = map.value;
+        args = loop {
+            let (child, _) = read_args(entries, args.count)?;
+            entries = &entries[args.count * 4..];
+            let parent = nexus.header.find_phandle(BigEndianU32::from_bytes(entries)?.get())?;
+            entries = &entries[4..];
+            let parent_count = property_parts(parent, &["#", stem, "-cells"])?.as_usize()?;
+            let (mut parent_args, _) = read_args(entries, parent_count)?;
+            entries = &entries[parent_count * 4..];
+
+            if (0..args.count).all(|i| child[i] == args.args[i] & mask[i]) {
+                let pass_thru = cells("-map-pass-thru", parent_count).unwrap_or([0; MAX_PHANDLE_ARGS]);
+                for (i, arg) in parent_args.iter_mut().enumerate().take(parent_count) {
+                    *arg = (*arg & !pass_thru[i]) | (args.args[i] & pass_thru[i]);
+                }
+                break PhandleArgs { node: parent, index: args.index, args: parent_args, count: parent_count };
+            }
+        };
+    }
+
+    None
+}
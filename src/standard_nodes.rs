@@ -236,19 +236,26 @@ impl<'a> Iterator for RegIter<'a> {
     type Item = MemoryRegion;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let (base, size) = self.next_raw()?;
+        Some(MemoryRegion { starting_address: base as usize as *const u8, size: size as usize })
+    }
+}
+
+impl RegIter<'_> {
+    /// Next `(address, size)` pair without truncating to the pointer width
+    pub(crate) fn next_raw(&mut self) -> Option<(u64, u64)> {
         let base = match self.sizes.address_cells {
-            1 => self.stream.u32()?.get() as usize,
-            2 => self.stream.u64()?.get() as usize,
+            1 => self.stream.u32()?.get() as u64,
+            2 => self.stream.u64()?.get(),
             _ => return None,
-        } as *const u8;
+        };
 
         let size = match self.sizes.size_cells {
-            1 => self.stream.u32()?.get() as usize,
-            2 => self.stream.u64()?.get() as usize,
+            1 => self.stream.u32()?.get() as u64,
+            2 => self.stream.u64()?.get(),
             _ => return None,
         };
 
-        Some(MemoryRegion { starting_address: base, size: size })
+        Some((base, size))
     }
 }
-
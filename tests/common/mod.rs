@@ -0,0 +1,13 @@
+//! Helpers shared by the integration tests
+
+#![allow(dead_code)]
+
+use fdtree_rs::DtsCompiler;
+
+/// The QEMU riscv virt reference blob
+pub static DTB_DATA: &[u8] = include_bytes!("../../dtb/test.dtb");
+
+/// Compiles an inline DTS source
+pub fn fdt(src: &str) -> Vec<u8> {
+    DtsCompiler::new().compile(src).unwrap()
+}
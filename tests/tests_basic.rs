@@ -66,7 +66,7 @@ fn linux_reserved_memory() {
     assert_eq!(vnode1.node.name, "static_buf@0000000080000000");
     assert_eq!(vnode1.nomap(), false);
 
-    let mut vreg1_iter = vnode1.regions();
+    let mut vreg1_iter = vnode1.regions().unwrap();
     assert_eq!(vreg1_iter.clone().count(), 1);
     let vreg1_0 = vreg1_iter.next().unwrap();
     assert_eq!(vreg1_0.starting_address as usize, 0x80000000);
@@ -76,7 +76,7 @@ fn linux_reserved_memory() {
     assert_eq!(vnode2.node.name, "secure_carveout@0000000090000000");
     assert_eq!(vnode2.nomap(), true);
 
-    let mut vreg2_iter = vnode2.regions();
+    let mut vreg2_iter = vnode2.regions().unwrap();
     assert_eq!(vreg2_iter.clone().count(), 2);
     let vreg2_0 = vreg2_iter.next().unwrap();
     assert_eq!(vreg2_0.starting_address as usize, 0x90000000);
@@ -96,21 +96,23 @@ fn linux_reserved_memory_dynamic() {
     let mut dyn_node_iter = reserved.dynamic_nodes();
     let dyn_node1 = dyn_node_iter.next().unwrap();
     assert_eq!(dyn_node1.node.name, "dyn_pool");
-    assert_eq!(dyn_node1.size(), 0x4000000);
-    assert_eq!(dyn_node1.alignment(), 0x200000);
+    assert_eq!(dyn_node1.size(), Ok(0x4000000));
+    assert_eq!(dyn_node1.alignment(), Ok(0x200000));
     assert_eq!(dyn_node1.nomap(), false);
     assert_eq!(dyn_node1.reusable(), false);
     assert_eq!(dyn_node1.shared_dma_pool(), false);
-    assert!(dyn_node1.alloc_ranges().is_none());
+    assert!(dyn_node1.alloc_ranges().unwrap().is_none());
 
     let dyn_node2 = dyn_node_iter.next().unwrap();
     assert_eq!(dyn_node2.node.name, "linux,cma");
-    assert_eq!(dyn_node2.size(), 0x10000000);
-    assert_eq!(dyn_node2.alignment(), 0x2000000);
+    assert_eq!(dyn_node2.size(), Ok(0x10000000));
+    assert_eq!(dyn_node2.alignment(), Ok(0x2000000));
     assert_eq!(dyn_node2.nomap(), false);
     assert_eq!(dyn_node2.reusable(), true);
     assert_eq!(dyn_node2.shared_dma_pool(), true);
-    assert!(dyn_node2.alloc_ranges().is_none());
+    assert!(dyn_node2.alloc_ranges().unwrap().is_none());
+
+    assert_eq!(reserved.invalid_nodes().count(), 0);
 }
 
 #[test]
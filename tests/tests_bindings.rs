@@ -0,0 +1,968 @@
+mod common;
+
+use common::{DTB_DATA, fdt};
+use fdtree_rs::{
+    GpioBias, GpioDrive, GpioFlags, GpioHogState, LegacyOpp, LinuxFdt, MacAddress, OppVoltage, PhyMode, PinBias,
+};
+
+static PHANDLE_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	chosen {
+	};
+
+	soc_gpio1: gpio-controller1 {
+		#gpio-cells = <2>;
+	};
+
+	soc_gpio2: gpio-controller2 {
+		#gpio-cells = <2>;
+	};
+
+	connector: connector {
+		#gpio-cells = <2>;
+		gpio-map = <0 0 &soc_gpio1 1 0>,
+			   <1 0 &soc_gpio2 4 0>,
+			   <2 0 &soc_gpio1 3 0>,
+			   <3 0 &soc_gpio2 2 0>;
+		gpio-map-mask = <0xf 0x0>;
+		gpio-map-pass-thru = <0x0 0x1>;
+	};
+
+	rst: reset-controller {
+		#reset-cells = <1>;
+	};
+
+	dma: dma-controller {
+		#dma-cells = <0>;
+	};
+
+	device {
+		reset-gpios = <&connector 2 1>, <&connector 0x13 0>, <&connector 7 0>;
+		resets = <&rst 4>, <0>, <&rst 9>;
+		reset-names = "core", "unused", "bus";
+		dmas = <&dma &dma>;
+		broken = <&rst>;
+	};
+};
+"#;
+
+#[test]
+fn phandle_args_lists() {
+    let dtb = fdt(PHANDLE_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let device = fdt.find_node("/device").unwrap();
+
+    let resets: Vec<_> =
+        device.phandle_args("resets", "#reset-cells").map(|r| (r.index, r.node.name, r.args().to_vec())).collect();
+    assert_eq!(resets, [(0, "reset-controller", vec![4]), (2, "reset-controller", vec![9])]);
+
+    let bus = device.phandle_args_by_name("resets", "#reset-cells", "reset-names", "bus").unwrap();
+    assert_eq!(bus.args(), [9]);
+    assert!(device.phandle_args_by_name("resets", "#reset-cells", "reset-names", "unused").is_none());
+
+    assert_eq!(device.phandle_args("dmas", "#dma-cells").count(), 2);
+    assert_eq!(device.phandle_args("broken", "#reset-cells").count(), 0);
+    assert_eq!(device.phandle_args("missing", "#reset-cells").count(), 0);
+}
+
+#[test]
+fn phandle_args_nexus_map() {
+    let dtb = fdt(PHANDLE_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let device = fdt.find_node("/device").unwrap();
+
+    // without the map the connector itself is the provider
+    let raw: Vec<_> = device.phandle_args("reset-gpios", "#gpio-cells").map(|g| g.node.name).collect();
+    assert_eq!(raw, ["connector", "connector", "connector"]);
+
+    // the mask drops the high bits of the line and the flags pass through;
+    // line 7 isn't mapped, so the walk stops there
+    let mapped: Vec<_> =
+        device.phandle_args_map("reset-gpios", "gpio").map(|g| (g.index, g.node.name, g.args().to_vec())).collect();
+    assert_eq!(mapped, [(0, "gpio-controller1", vec![3, 1]), (1, "gpio-controller2", vec![2, 0])]);
+}
+
+static CLOCK_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	chosen {
+	};
+
+	osc24m: osc24m {
+		compatible = "fixed-clock";
+		#clock-cells = <0>;
+		clock-frequency = <24000000>;
+	};
+
+	pll: pll {
+		compatible = "fixed-factor-clock";
+		#clock-cells = <0>;
+		clocks = <&osc24m>;
+		clock-mult = <50>;
+		clock-div = <2>;
+	};
+
+	ccu: clock-controller@1000 {
+		reg = <0x1000 0x100>;
+		#clock-cells = <1>;
+		clock-output-names = "bus", "mmc", "uart";
+	};
+
+	mmc@2000 {
+		reg = <0x2000 0x100>;
+		clocks = <&ccu 0>, <&ccu 1>, <&pll>;
+		clock-names = "ahb", "mmc", "ref";
+		assigned-clocks = <&ccu 1>, <&ccu 2>;
+		assigned-clock-rates = <50000000>, <0>;
+		assigned-clock-parents = <0>, <&pll>;
+	};
+};
+"#;
+
+#[test]
+fn clock_consumers() {
+    let dtb = fdt(CLOCK_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let mmc = fdt.find_node("/mmc@2000").unwrap();
+
+    let clocks: Vec<_> = mmc.clocks().map(|c| (c.name, c.provider().name, c.specifier().to_vec())).collect();
+    assert_eq!(
+        clocks,
+        [
+            (Some("ahb"), "clock-controller@1000", vec![0]),
+            (Some("mmc"), "clock-controller@1000", vec![1]),
+            (Some("ref"), "pll", vec![]),
+        ]
+    );
+
+    let mmc_clk = mmc.clock("mmc").unwrap();
+    assert_eq!(mmc_clk.index(), 1);
+    assert_eq!(mmc_clk.output_name(), Some("mmc"));
+    assert_eq!(mmc_clk.rate(), None);
+    assert_eq!(mmc.clock("ref").unwrap().rate(), Some(600_000_000));
+    assert!(mmc.clock("missing").is_none());
+
+    let osc = fdt.find_node("/osc24m").unwrap();
+    assert_eq!(osc.clocks().count(), 0);
+    assert_eq!(fdt.find_node("/pll").unwrap().clocks().next().unwrap().rate(), Some(24_000_000));
+}
+
+#[test]
+fn clock_assigned() {
+    let dtb = fdt(CLOCK_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let mmc = fdt.find_node("/mmc@2000").unwrap();
+
+    let assigned: Vec<_> = mmc.assigned_clocks().collect();
+    assert_eq!(assigned.len(), 2);
+    assert_eq!(assigned[0].clock.specifier(), [1]);
+    assert_eq!(assigned[0].rate, Some(50_000_000));
+    assert!(assigned[0].parent.is_none());
+    assert_eq!(assigned[1].clock.specifier(), [2]);
+    assert_eq!(assigned[1].rate, None);
+    assert_eq!(assigned[1].parent.unwrap().provider().name, "pll");
+}
+
+#[test]
+fn clock_reference_blob() {
+    let fdt = LinuxFdt::new(DTB_DATA).unwrap();
+    let uart = fdt.find_node("/soc/uart@10000000").unwrap();
+    assert_eq!(uart.clocks().count(), 0);
+    assert_eq!(uart.assigned_clocks().count(), 0);
+}
+
+static GPIO_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	pinctrl: pinctrl@1000 {
+		reg = <0x1000 0x100>;
+	};
+
+	gpio: gpio@2000 {
+		reg = <0x2000 0x100>;
+		gpio-controller;
+		#gpio-cells = <2>;
+		ngpios = <8>;
+		gpio-line-names = "led", "", "reset";
+		gpio-ranges = <&pinctrl 0 16 4>, <&pinctrl 4 0 0>;
+		gpio-ranges-group-names = "", "uart0";
+
+		wifi-en-hog {
+			gpio-hog;
+			gpios = <5 0>, <6 1>;
+			output-high;
+			line-name = "wifi-en";
+		};
+
+		disabled-hog {
+			gpio-hog;
+			gpios = <7 0>;
+			input;
+			status = "disabled";
+		};
+	};
+
+	connector: connector {
+		#gpio-cells = <2>;
+		gpio-map = <0 0 &gpio 3 0>, <1 0 &gpio 4 0>;
+		gpio-map-mask = <0xf 0x0>;
+		gpio-map-pass-thru = <0x0 0x1>;
+	};
+
+	device {
+		reset-gpios = <&gpio 2 0x01>;
+		enable-gpio = <&gpio 1 0x26>;
+		gpios = <&connector 1 1>, <0>, <&gpio 0 0x08>;
+	};
+};
+"#;
+
+#[test]
+fn gpio_consumers() {
+    let dtb = fdt(GPIO_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let device = fdt.find_node("/device").unwrap();
+
+    let reset = device.gpios("reset").next().unwrap();
+    assert_eq!(reset.controller().name, "gpio@2000");
+    assert_eq!(reset.line(), 2);
+    assert!(reset.flags().active_low());
+    assert_eq!(reset.flags().drive(), GpioDrive::PushPull);
+
+    let enable = device.gpios("enable").next().unwrap();
+    assert_eq!(enable.line(), 1);
+    assert_eq!(enable.flags(), GpioFlags(0x26));
+    assert_eq!(enable.flags().drive(), GpioDrive::OpenDrain);
+    assert_eq!(enable.flags().bias(), GpioBias::PullDown);
+    assert_eq!(GpioFlags(GpioFlags::SINGLE_ENDED).drive(), GpioDrive::OpenSource);
+
+    let plain: Vec<_> = device.gpios("").map(|g| (g.controller().name, g.specifier().to_vec())).collect();
+    assert_eq!(plain, [("gpio@2000", vec![4, 1]), ("gpio@2000", vec![0, 8])]);
+    assert!(device.gpios("").nth(1).unwrap().flags().transitory());
+
+    assert_eq!(device.gpios("missing").count(), 0);
+}
+
+#[test]
+fn gpio_controller() {
+    let dtb = fdt(GPIO_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    assert!(fdt.find_node("/device").unwrap().gpio_controller().is_none());
+
+    let gpio = fdt.find_node("/gpio@2000").unwrap().gpio_controller().unwrap();
+    assert_eq!(gpio.gpio_cells(), Some(2));
+    assert_eq!(gpio.ngpios(), Some(8));
+    assert_eq!(gpio.line_names().collect::<Vec<_>>(), ["led", "", "reset"]);
+    assert_eq!(gpio.line_name(0), Some("led"));
+    assert_eq!(gpio.line_name(1), None);
+    assert_eq!(gpio.line_name(5), None);
+
+    let ranges: Vec<_> =
+        gpio.ranges().map(|r| (r.pinctrl.name, r.gpio_offset, r.pin_offset, r.count, r.group)).collect();
+    assert_eq!(ranges, [("pinctrl@1000", 0, 16, 4, None), ("pinctrl@1000", 4, 0, 0, Some("uart0"))]);
+
+    let hogs: Vec<_> = gpio.hogs().collect();
+    assert_eq!(hogs.len(), 1);
+    assert_eq!(hogs[0].state(), Some(GpioHogState::OutputHigh));
+    assert_eq!(hogs[0].line_name(), Some("wifi-en"));
+    assert_eq!(hogs[0].lines().collect::<Vec<_>>(), [(5, GpioFlags(0)), (6, GpioFlags(GpioFlags::ACTIVE_LOW))]);
+}
+
+static PINCTRL_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	pinctrl@1000 {
+		reg = <0x1000 0x100>;
+
+		uart0_default: uart0-default {
+			tx-pins {
+				pins = "PA0";
+				function = "uart0";
+				bias-disable;
+				drive-strength = <8>;
+			};
+
+			rx-pins {
+				pins = "PA1";
+				function = "uart0";
+				bias-pull-up = <47000>;
+				input-enable;
+			};
+		};
+
+		uart0_sleep: uart0-sleep {
+			groups = "uart0_grp", "uart0_rts";
+			function = "gpio";
+			bias-pull-down;
+			input-disable;
+			drive-strength-microamp = <500>;
+		};
+	};
+
+	serial@2000 {
+		reg = <0x2000 0x100>;
+		pinctrl-names = "default", "sleep", "idle";
+		pinctrl-0 = <&uart0_default>;
+		pinctrl-1 = <&uart0_sleep>;
+		pinctrl-2 = <>;
+		pinctrl-4 = <&uart0_sleep>;
+	};
+};
+"#;
+
+#[test]
+fn pinctrl_states() {
+    let dtb = fdt(PINCTRL_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let serial = fdt.find_node("/serial@2000").unwrap();
+
+    let states: Vec<_> = serial.pinctrl_states().map(|s| (s.id, s.name, s.configs().count())).collect();
+    assert_eq!(states, [(0, Some("default"), 1), (1, Some("sleep"), 1), (2, Some("idle"), 0)]);
+
+    let default = serial.pinctrl_state("default").unwrap().configs().next().unwrap();
+    assert_eq!(default.node.name, "uart0-default");
+    assert!(serial.pinctrl_state("missing").is_none());
+    assert_eq!(fdt.find_node("/pinctrl@1000").unwrap().pinctrl_states().count(), 0);
+
+    let pins: Vec<_> = default.subconfigs().collect();
+    assert_eq!(pins[0].pins().collect::<Vec<_>>(), ["PA0"]);
+    assert_eq!(pins[0].function(), Some("uart0"));
+    assert_eq!(pins[0].bias(), Some(PinBias::Disable));
+    assert_eq!(pins[0].drive_strength(), Some(8));
+    assert_eq!(pins[0].input_enable(), None);
+    assert_eq!(pins[1].bias(), Some(PinBias::PullUp(Some(47000))));
+    assert_eq!(pins[1].input_enable(), Some(true));
+}
+
+#[test]
+fn pinctrl_groups() {
+    let dtb = fdt(PINCTRL_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let serial = fdt.find_node("/serial@2000").unwrap();
+
+    let sleep = serial.pinctrl_state("sleep").unwrap().configs().next().unwrap();
+    assert_eq!(sleep.pins().count(), 0);
+    assert_eq!(sleep.groups().collect::<Vec<_>>(), ["uart0_grp", "uart0_rts"]);
+    assert_eq!(sleep.function(), Some("gpio"));
+    assert_eq!(sleep.bias(), Some(PinBias::PullDown(None)));
+    assert_eq!(sleep.input_enable(), Some(false));
+    assert_eq!(sleep.drive_strength(), None);
+    assert_eq!(sleep.drive_strength_microamp(), Some(500));
+    assert_eq!(sleep.subconfigs().count(), 0);
+}
+
+static REGULATOR_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	gpio: gpio@1000 {
+		reg = <0x1000 0x100>;
+		gpio-controller;
+		#gpio-cells = <2>;
+	};
+
+	pd: power-controller@2000 {
+		reg = <0x2000 0x100>;
+		#power-domain-cells = <1>;
+	};
+
+	pd_always: power-controller-always {
+		#power-domain-cells = <0>;
+	};
+
+	vcc_3v3: regulator-3v3 {
+		compatible = "regulator-fixed";
+		regulator-name = "vcc-3v3";
+		regulator-min-microvolt = <3300000>;
+		regulator-max-microvolt = <3300000>;
+		regulator-always-on;
+		gpio = <&gpio 4 0>;
+		enable-active-high;
+		startup-delay-us = <100>;
+	};
+
+	vdd_core: regulator-core {
+		regulator-name = "vdd-core";
+		regulator-min-microvolt = <800000>;
+		regulator-max-microvolt = <1100000>;
+		regulator-max-microamp = <2000000>;
+		regulator-boot-on;
+	};
+
+	mmc@3000 {
+		reg = <0x3000 0x100>;
+		vmmc-supply = <&vcc_3v3>;
+		vqmmc-supply = <&vdd_core>;
+		power-domains = <&pd 3>, <&pd_always>;
+		power-domain-names = "io", "core";
+	};
+};
+"#;
+
+#[test]
+fn regulator_supplies() {
+    let dtb = fdt(REGULATOR_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let mmc = fdt.find_node("/mmc@3000").unwrap();
+
+    let supplies: Vec<_> = mmc.supplies().map(|s| (s.name, s.regulator.name())).collect();
+    assert_eq!(supplies, [("vmmc", Some("vcc-3v3")), ("vqmmc", Some("vdd-core"))]);
+    assert!(mmc.supply("vcc").is_none());
+
+    let vmmc = mmc.supply("vmmc").unwrap();
+    assert!(vmmc.is_fixed());
+    assert_eq!(vmmc.fixed_microvolt(), Some(3_300_000));
+    assert!(vmmc.always_on());
+    assert!(!vmmc.boot_on());
+    assert_eq!(vmmc.enable_gpio().unwrap().line(), 4);
+    assert!(vmmc.enable_active_high());
+    assert_eq!(vmmc.startup_delay_us(), Some(100));
+
+    let vqmmc = mmc.supply("vqmmc").unwrap();
+    assert!(!vqmmc.is_fixed());
+    assert_eq!(vqmmc.fixed_microvolt(), None);
+    assert_eq!((vqmmc.min_microvolt(), vqmmc.max_microvolt()), (Some(800_000), Some(1_100_000)));
+    assert_eq!((vqmmc.min_microamp(), vqmmc.max_microamp()), (None, Some(2_000_000)));
+    assert!(vqmmc.boot_on());
+    assert!(vqmmc.enable_gpio().is_none());
+}
+
+#[test]
+fn power_domains() {
+    let dtb = fdt(REGULATOR_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let mmc = fdt.find_node("/mmc@3000").unwrap();
+
+    let domains: Vec<_> = mmc.power_domains().map(|d| (d.name, d.provider().name, d.specifier().to_vec())).collect();
+    assert_eq!(
+        domains,
+        [(Some("io"), "power-controller@2000", vec![3]), (Some("core"), "power-controller-always", vec![])]
+    );
+    assert_eq!(mmc.power_domain("core").unwrap().index(), 1);
+    assert!(mmc.power_domain("gpu").is_none());
+    assert_eq!(fdt.find_node("/gpio@1000").unwrap().power_domains().count(), 0);
+}
+
+static IOMMU_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	rst: reset-controller@1000 {
+		reg = <0x1000 0x100>;
+		#reset-cells = <1>;
+	};
+
+	smmu: iommu@2000 {
+		reg = <0x2000 0x1000>;
+		#iommu-cells = <1>;
+	};
+
+	its: msi-controller@3000 {
+		reg = <0x3000 0x1000>;
+		msi-controller;
+		#msi-cells = <1>;
+	};
+
+	its2: msi-controller@4000 {
+		reg = <0x4000 0x1000>;
+		msi-controller;
+		#msi-cells = <1>;
+	};
+
+	dma@5000 {
+		reg = <0x5000 0x100>;
+		iommus = <&smmu 0x20>, <&smmu 0x21>;
+		resets = <&rst 4>, <&rst 5>;
+		reset-names = "core", "bus";
+	};
+
+	pcie@6000 {
+		reg = <0x6000 0x100>;
+		iommu-map = <0x0 &smmu 0x10000 0x100>, <0x100 &smmu 0x0 0x100>;
+		iommu-map-mask = <0xfff8>;
+		msi-map = <0x0 &its 0x0 0x10000>, <0x0 &its2 0x8000 0x10000>;
+	};
+};
+"#;
+
+#[test]
+fn resets_and_iommus() {
+    let dtb = fdt(IOMMU_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let dma = fdt.find_node("/dma@5000").unwrap();
+
+    let resets: Vec<_> = dma.resets().map(|r| (r.name, r.controller().name, r.specifier().to_vec())).collect();
+    assert_eq!(
+        resets,
+        [(Some("core"), "reset-controller@1000", vec![4]), (Some("bus"), "reset-controller@1000", vec![5])]
+    );
+    assert_eq!(dma.reset("bus").unwrap().index(), 1);
+    assert!(dma.reset("phy").is_none());
+
+    let iommus: Vec<_> = dma.iommus().map(|i| (i.iommu().name, i.stream_id())).collect();
+    assert_eq!(iommus, [("iommu@2000", Some(0x20)), ("iommu@2000", Some(0x21))]);
+    assert_eq!(dma.iommus().next().unwrap().specifier(), [0x20]);
+}
+
+#[test]
+fn bus_id_maps() {
+    let dtb = fdt(IOMMU_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let pcie = fdt.find_node("/pcie@6000").unwrap();
+
+    let iommu = pcie.iommu_map(0x0a).unwrap();
+    assert_eq!((iommu.target.name, iommu.id), ("iommu@2000", 0x10008));
+    let iommu = pcie.iommu_map(0x10f).unwrap();
+    assert_eq!((iommu.target.name, iommu.id), ("iommu@2000", 0x08));
+    assert!(pcie.iommu_map(0x200).is_none());
+
+    let msi = pcie.msi_map(0x42).unwrap();
+    assert_eq!((msi.target.name, msi.id), ("msi-controller@3000", 0x42));
+    let msi: Vec<_> = pcie.map_ids("msi-map", 0x42).map(|m| (m.target.name, m.id)).collect();
+    assert_eq!(msi, [("msi-controller@3000", 0x42), ("msi-controller@4000", 0x8042)]);
+
+    let dma = fdt.find_node("/dma@5000").unwrap();
+    assert!(dma.msi_map(0).is_none());
+}
+
+static OPP_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	cpus {
+		#address-cells = <1>;
+		#size-cells = <0>;
+
+		cpu@0 {
+			device_type = "cpu";
+			reg = <0>;
+			operating-points-v2 = <&cpu_opp_table>;
+		};
+
+		cpu@1 {
+			device_type = "cpu";
+			reg = <1>;
+			operating-points = <
+				/* kHz    uV */
+				1000000 1100000
+				500000  950000
+			>;
+		};
+	};
+
+	cpu_opp_table: opp-table {
+		compatible = "operating-points-v2";
+		opp-shared;
+
+		opp-500000000 {
+			opp-hz = /bits/ 64 <500000000>;
+			opp-microvolt = <900000>;
+			clock-latency-ns = <300000>;
+			opp-suspend;
+			required-opps = <&bus_low>;
+		};
+
+		opp-1000000000 {
+			opp-hz = /bits/ 64 <1000000000>;
+			opp-microvolt = <1000000 950000 1050000>;
+			opp-microvolt-fast = <975000>;
+			opp-microamp = <70000>;
+			clock-latency-ns = <300000>;
+		};
+
+		opp-5000000000 {
+			opp-hz = /bits/ 64 <5000000000>;
+			opp-microvolt = <1200000 1100000>;
+			turbo-mode;
+		};
+
+		opp-disabled {
+			opp-hz = /bits/ 64 <6000000000>;
+			status = "disabled";
+		};
+	};
+
+	bus_opp_table: opp-table-bus {
+		compatible = "operating-points-v2";
+
+		bus_low: opp-low {
+			opp-hz = /bits/ 64 <100000000 200000000>;
+		};
+	};
+
+	gpu {
+		operating-points-v2 = <&gpu_opp_table>;
+	};
+
+	gpu_opp_table: gpu-opp-table {
+		compatible = "vendor,gpu-opp";
+
+		opp0 {
+			opp-hz = /bits/ 64 <200000000>;
+		};
+	};
+
+	dsp {
+		operating-points-v2 = <&kryo_opp_table>;
+	};
+
+	kryo_opp_table: opp-table-kryo {
+		compatible = "operating-points-v2-kryo-cpu";
+
+		opp-307200000 {
+			opp-hz = /bits/ 64 <307200000>;
+		};
+	};
+};
+"#;
+
+#[test]
+fn opp_v2_table() {
+    let dtb = fdt(OPP_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let cpu = fdt.cpus().next().unwrap();
+    let table = cpu.opp_table().unwrap();
+
+    assert_eq!(table.node().name, "opp-table");
+    assert!(table.shared());
+    let opps: Vec<_> = table.opps().collect();
+    assert_eq!(opps.iter().map(|opp| opp.hz()).collect::<Vec<_>>(), [
+        Some(500_000_000),
+        Some(1_000_000_000),
+        Some(5_000_000_000)
+    ]);
+    assert_eq!(table.suspend_opp().unwrap().node.name, "opp-500000000");
+
+    let single = OppVoltage { target: 900_000, min: 900_000, max: 900_000 };
+    assert_eq!(opps[0].microvolt(1).collect::<Vec<_>>(), [single]);
+    assert_eq!(opps[0].clock_latency_ns(), Some(300_000));
+    assert!(!opps[0].turbo_mode());
+    let required: Vec<_> = opps[0].required_opps().collect();
+    assert_eq!(required[0].rates().collect::<Vec<_>>(), [100_000_000, 200_000_000]);
+
+    let triplet = OppVoltage { target: 1_000_000, min: 950_000, max: 1_050_000 };
+    assert_eq!(opps[1].microvolt(1).collect::<Vec<_>>(), [triplet]);
+    assert_eq!(opps[1].microvolt_named("fast", 1).next().unwrap().target, 975_000);
+    assert_eq!(opps[1].microvolt_named("slow", 1).count(), 0);
+    assert_eq!(opps[1].microamp().collect::<Vec<_>>(), [70_000]);
+    assert_eq!(opps[1].microvolt(2).count(), 0);
+
+    let per_supply: Vec<_> = opps[2].microvolt(2).map(|v| v.target).collect();
+    assert_eq!(per_supply, [1_200_000, 1_100_000]);
+    assert_eq!(opps[2].microvolt(1).count(), 0);
+    assert!(opps[2].turbo_mode());
+    assert!(!opps[2].suspend());
+}
+
+#[test]
+fn opp_vendor_table() {
+    let dtb = fdt(OPP_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let table = fdt.find_node("/dsp").unwrap().opp_table().unwrap();
+
+    assert_eq!(table.node().name, "opp-table-kryo");
+    assert_eq!(table.opps().map(|opp| opp.hz()).collect::<Vec<_>>(), [Some(307_200_000)]);
+
+    // OPP nodes don't have to be named `opp-*`
+    let table = fdt.find_node("/gpu").unwrap().opp_table().unwrap();
+    assert_eq!(table.node().name, "gpu-opp-table");
+    assert_eq!(table.opps().map(|opp| opp.hz()).collect::<Vec<_>>(), [Some(200_000_000)]);
+}
+
+#[test]
+fn opp_legacy() {
+    let dtb = fdt(OPP_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let cpus: Vec<_> = fdt.cpus().collect();
+
+    assert!(cpus[1].opp_table().is_none());
+    assert_eq!(cpus[0].node().operating_points().count(), 0);
+    assert_eq!(cpus[1].node().operating_points().collect::<Vec<_>>(), [
+        LegacyOpp { frequency_khz: 1_000_000, microvolt: 1_100_000 },
+        LegacyOpp { frequency_khz: 500_000, microvolt: 950_000 },
+    ]);
+}
+
+static NVMEM_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	eeprom@50 {
+		compatible = "atmel,24c02";
+		reg = <0x50 0x100>;
+
+		nvmem-layout {
+			compatible = "fixed-layout";
+			#address-cells = <1>;
+			#size-cells = <1>;
+
+			eth_mac: mac-address@fa {
+				reg = <0xfa 0x6>;
+			};
+
+			base_mac: mac-base@100 {
+				compatible = "mac-base";
+				reg = <0x100 0x6>;
+				#nvmem-cell-cells = <1>;
+			};
+		};
+	};
+
+	efuse@1000 {
+		reg = <0x1000 0x100>;
+		#address-cells = <1>;
+		#size-cells = <1>;
+
+		calib: calib@8 {
+			reg = <0x8 0x2>;
+			bits = <4 10>;
+		};
+	};
+
+	ethernet@2000 {
+		reg = <0x2000 0x100>;
+		nvmem-cells = <&eth_mac>, <&calib>, <&base_mac 2>;
+		nvmem-cell-names = "mac-address", "calibration", "base";
+	};
+
+	ethernet@3000 {
+		reg = <0x3000 0x100>;
+		mac-address = [00 00 00 00 00 00];
+		local-mac-address = [02 11 22 33 44 55];
+		nvmem-cells = <&eth_mac>;
+		nvmem-cell-names = "mac-address";
+	};
+
+	ethernet@4000 {
+		reg = <0x4000 0x100>;
+		local-mac-address = [01 00 5e 00 00 01];
+	};
+};
+"#;
+
+#[test]
+fn nvmem_cells() {
+    let dtb = fdt(NVMEM_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let eth = fdt.find_node("/ethernet@2000").unwrap();
+
+    let cells: Vec<_> = eth.nvmem_cells().map(|c| (c.name, c.provider().unwrap().name, c.offset(), c.size())).collect();
+    assert_eq!(
+        cells,
+        [
+            (Some("mac-address"), "eeprom@50", Some(0xfa), Some(6)),
+            (Some("calibration"), "efuse@1000", Some(0x8), Some(2)),
+            (Some("base"), "eeprom@50", Some(0x100), Some(6)),
+        ]
+    );
+
+    let calib = eth.nvmem_cell("calibration").unwrap();
+    assert_eq!(calib.node().name, "calib@8");
+    assert_eq!(calib.bits(), Some((4, 10)));
+    assert!(calib.specifier().is_empty());
+    assert_eq!(eth.nvmem_cell("base").unwrap().specifier(), [2]);
+    assert_eq!(eth.nvmem_cell("mac-address").unwrap().bits(), None);
+    assert!(eth.nvmem_cell("serial").is_none());
+}
+
+#[test]
+fn mac_address_precedence() {
+    let dtb = fdt(NVMEM_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+
+    let mac = fdt.find_node("/ethernet@3000").unwrap().mac_address();
+    assert!(matches!(mac, Some(MacAddress::Fixed([0x02, 0x11, 0x22, 0x33, 0x44, 0x55]))));
+
+    let mac = fdt.find_node("/ethernet@2000").unwrap().mac_address();
+    let Some(MacAddress::Nvmem(cell)) = mac else { panic!("expected an NVMEM MAC address") };
+    assert_eq!(cell.offset(), Some(0xfa));
+
+    assert!(fdt.find_node("/ethernet@4000").unwrap().mac_address().is_none());
+}
+
+static ETHERNET_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	gpio: gpio@1000 {
+		reg = <0x1000 0x100>;
+		gpio-controller;
+		#gpio-cells = <2>;
+	};
+
+	ethernet@2000 {
+		reg = <0x2000 0x1000>;
+		phy-mode = "rgmii-id";
+		phy-handle = <&phy1>;
+
+		mdio {
+			#address-cells = <1>;
+			#size-cells = <0>;
+
+			phy1: ethernet-phy@1 {
+				compatible = "ethernet-phy-id001c.c916", "ethernet-phy-ieee802.3-c22";
+				reg = <1>;
+			};
+
+			ethernet-phy@4 {
+				compatible = "ethernet-phy-ieee802.3-c45";
+				reg = <4>;
+			};
+
+			ethernet-phy@5 {
+				reg = <5>;
+				status = "disabled";
+			};
+
+			switch: switch@10 {
+				compatible = "vendor,switch";
+				reg = <0x10>;
+
+				ethernet-ports {
+					#address-cells = <1>;
+					#size-cells = <0>;
+
+					port@0 {
+						reg = <0>;
+						label = "lan0";
+						phy-mode = "internal";
+					};
+
+					port@1 {
+						reg = <1>;
+						status = "disabled";
+					};
+
+					port@5 {
+						reg = <5>;
+						ethernet = <&cpu_mac>;
+						phy-connection-type = "sgmii";
+
+						fixed-link {
+							speed = <1000>;
+							full-duplex;
+							pause;
+							link-gpios = <&gpio 3 0>;
+						};
+					};
+				};
+			};
+		};
+	};
+
+	cpu_mac: ethernet@3000 {
+		reg = <0x3000 0x1000>;
+		phy-mode = "10gbase-r";
+		phy = <&phy1>;
+		fixed-link = <0 1 100 0 1>;
+	};
+
+	ethernet@4000 {
+		reg = <0x4000 0x1000>;
+		phy-mode = "bogus";
+		fixed-link = <0 1 100>;
+	};
+};
+"#;
+
+#[test]
+fn phy_handle_and_mode() {
+    let dtb = fdt(ETHERNET_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+
+    let eth = fdt.find_node("/ethernet@2000").unwrap();
+    assert_eq!(eth.phy_handle().unwrap().name, "ethernet-phy@1");
+    assert_eq!(eth.phy_mode(), Some(PhyMode::RgmiiId));
+    assert!(eth.phy_mode().unwrap().is_rgmii());
+    assert!(eth.fixed_link().is_none());
+
+    let cpu_mac = fdt.find_node("/ethernet@3000").unwrap();
+    assert_eq!(cpu_mac.phy_handle().unwrap().name, "ethernet-phy@1");
+    assert_eq!(cpu_mac.phy_mode(), Some(PhyMode::Base10gR));
+
+    let bogus = fdt.find_node("/ethernet@4000").unwrap();
+    assert_eq!(bogus.phy_mode(), None);
+    assert!(bogus.phy_handle().is_none());
+    assert!(bogus.fixed_link().is_none());
+}
+
+#[test]
+fn fixed_link() {
+    let dtb = fdt(ETHERNET_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+
+    let legacy = fdt.find_node("/ethernet@3000").unwrap().fixed_link().unwrap();
+    assert_eq!(legacy.speed, 100);
+    assert!(legacy.full_duplex && !legacy.pause && legacy.asym_pause);
+    assert!(legacy.link_gpio.is_none());
+
+    let port = fdt.find_node("/ethernet@2000/mdio/switch@10/ethernet-ports/port@5").unwrap();
+    let link = port.fixed_link().unwrap();
+    assert_eq!(link.speed, 1000);
+    assert!(link.full_duplex && link.pause && !link.asym_pause);
+    let gpio = link.link_gpio.unwrap();
+    assert_eq!(gpio.controller().name, "gpio@1000");
+    assert_eq!(gpio.line(), 3);
+}
+
+#[test]
+fn mdio_phys() {
+    let dtb = fdt(ETHERNET_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let mdio = fdt.find_node("/ethernet@2000").unwrap().mdio().unwrap();
+
+    let phys: Vec<_> = mdio.phys().map(|phy| (phy.address(), phy.id(), phy.is_c45())).collect();
+    assert_eq!(phys, [(Some(1), Some(0x001c_c916), false), (Some(4), None, true), (Some(0x10), None, false)]);
+
+    assert_eq!(mdio.phy(4).unwrap().node.name, "ethernet-phy@4");
+    assert!(mdio.phy(5).is_none());
+    assert!(fdt.find_node("/ethernet@3000").unwrap().mdio().is_none());
+}
+
+#[test]
+fn switch_ports() {
+    let dtb = fdt(ETHERNET_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let switch = fdt.find_node("/ethernet@2000/mdio/switch@10").unwrap();
+
+    let ports: Vec<_> = switch.ethernet_ports().map(|port| (port.index(), port.label(), port.is_cpu_port())).collect();
+    assert_eq!(ports, [(Some(0), Some("lan0"), false), (Some(5), None, true)]);
+
+    let cpu = switch.ethernet_ports().find(|port| port.is_cpu_port()).unwrap();
+    assert_eq!(cpu.ethernet().unwrap().name, "ethernet@3000");
+    assert_eq!(cpu.node.phy_mode(), Some(PhyMode::Sgmii));
+    assert_eq!(switch.ethernet_ports().next().unwrap().node.phy_mode(), Some(PhyMode::Internal));
+    assert_eq!(fdt.find_node("/ethernet@3000").unwrap().ethernet_ports().count(), 0);
+}
@@ -0,0 +1,301 @@
+mod common;
+
+use common::{DTB_DATA, fdt};
+use fdtree_rs::chosen::UefiParams;
+use fdtree_rs::framebuffer::PixelFormat;
+use fdtree_rs::serial::{ConsoleSpec, EarlyConsole, Parity, UartKind};
+use fdtree_rs::{LinuxFdt, Uart};
+
+static CHOSEN_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <1>;
+
+	aliases {
+		serial0 = "/uart@9000000";
+	};
+
+	chosen {
+		stdout-path = "/uart@9000000:115200n8";
+		stdin-path = "serial0:9600";
+		linux,initrd-start = <0x48000000>;
+		linux,initrd-end = <0x0 0x48400000>;
+		kaslr-seed = <0x01234567 0x89abcdef>;
+		rng-seed = [de ad be ef];
+		linux,elfcorehdr = <0x1 0x0 0x10000>;
+		linux,booted-from-kexec;
+		linux,uefi-system-table = <0x0 0xbfe00000>;
+		linux,uefi-mmap-start = <0x0 0xbfd00000>;
+		linux,uefi-mmap-size = <0x1200>;
+		linux,uefi-mmap-desc-size = <0x30>;
+		linux,uefi-mmap-desc-ver = <0x1>;
+		linux,ima-kexec-buffer = <0x0 0x50000000 0x4000>;
+		linux,tpm-kexec-buffer = <0x0 0x50004000 0x2000>;
+	};
+
+	uart@9000000 {
+		reg = <0x0 0x9000000 0x1000>;
+	};
+};
+"#;
+
+#[test]
+fn chosen_boot_properties() {
+    let dtb = fdt(CHOSEN_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let chosen = fdt.chosen();
+
+    assert_eq!(chosen.initrd(), Some(0x4800_0000..0x4840_0000));
+    assert_eq!(chosen.kaslr_seed(), Some(0x0123_4567_89ab_cdef));
+    assert_eq!(chosen.rng_seed(), Some(&[0xde, 0xad, 0xbe, 0xef][..]));
+    assert_eq!(chosen.elfcorehdr(), Some(0x1_0000_0000..0x1_0001_0000));
+    assert!(chosen.booted_from_kexec());
+    assert_eq!(
+        chosen.uefi(),
+        Some(UefiParams {
+            system_table: 0xbfe0_0000,
+            mmap_start: 0xbfd0_0000,
+            mmap_size: 0x1200,
+            mmap_desc_size: 0x30,
+            mmap_desc_version: 1,
+        })
+    );
+    assert_eq!(chosen.ima_kexec_buffer(), Some(0x5000_0000..0x5000_4000));
+    assert_eq!(chosen.tpm_kexec_buffer(), Some(0x5000_4000..0x5000_6000));
+
+    let stdout = chosen.stdout().unwrap();
+    assert_eq!(stdout.node.name, "uart@9000000");
+    assert_eq!(stdout.options, Some("115200n8"));
+    let stdin = chosen.stdin().unwrap();
+    assert_eq!(stdin.node.name, "uart@9000000");
+    assert_eq!(stdin.options, Some("9600"));
+}
+
+#[test]
+fn chosen_missing_properties() {
+    let dtb =
+        fdt("/dts-v1/;\n/ {\n\tchosen {\n\t\tlinux,initrd-start = <0x2000>;\n\t\tkaslr-seed = <0x1>;\n\t};\n};\n");
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let chosen = fdt.chosen();
+
+    // initrd needs both ends, kaslr-seed is always 64-bit
+    assert_eq!(chosen.initrd(), None);
+    assert_eq!(chosen.kaslr_seed(), None);
+    assert_eq!(chosen.rng_seed(), None);
+    assert_eq!(chosen.elfcorehdr(), None);
+    assert!(!chosen.booted_from_kexec());
+    assert_eq!(chosen.uefi(), None);
+    assert!(chosen.stdin().is_none());
+}
+
+static SERIAL_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+
+	chosen {
+		stdout-path = "serial0";
+	};
+
+	aliases {
+		serial0 = &uart0;
+	};
+
+	soc@0 {
+		compatible = "simple-bus";
+		#address-cells = <1>;
+		#size-cells = <1>;
+		ranges = <0x0 0x0 0xfe000000 0x1000000>;
+
+		uart0: serial@201000 {
+			compatible = "arm,pl011", "arm,primecell";
+			reg = <0x201000 0x200>;
+			clock-frequency = <48000000>;
+			current-speed = <115200>;
+		};
+
+		serial@215040 {
+			compatible = "snps,dw-apb-uart";
+			reg = <0x215040 0x40>;
+			reg-shift = <2>;
+			reg-io-width = <4>;
+		};
+
+		serial@2000000 {
+			compatible = "ns16550a";
+			reg = <0x2000000 0x100>;
+		};
+	};
+
+	isolated {
+		#address-cells = <1>;
+		#size-cells = <1>;
+
+		serial@1000 {
+			compatible = "sifive,uart0";
+			reg = <0x1000 0x100>;
+		};
+	};
+};
+"#;
+
+#[test]
+fn console_spec_parse() {
+    let spec = ConsoleSpec::parse("115200n8").unwrap();
+    assert_eq!(spec, ConsoleSpec::new(115200));
+
+    let spec = ConsoleSpec::parse("9600e7r").unwrap();
+    assert_eq!(spec.baud, 9600);
+    assert_eq!(spec.parity, Parity::Even);
+    assert_eq!(spec.data_bits, 7);
+    assert!(spec.flow_control);
+
+    assert_eq!(ConsoleSpec::parse("38400o"), Some(ConsoleSpec { parity: Parity::Odd, ..ConsoleSpec::new(38400) }));
+    assert_eq!(ConsoleSpec::parse("n8"), None);
+    assert_eq!(ConsoleSpec::parse("115200x8"), None);
+    assert_eq!(ConsoleSpec::parse("115200n9"), None);
+}
+
+#[test]
+fn uart_early_console() {
+    let fdt = LinuxFdt::new(DTB_DATA).unwrap();
+    assert_eq!(
+        fdt.chosen().early_console(),
+        Some(EarlyConsole {
+            kind: UartKind::Ns16550,
+            base: 0x1000_0000,
+            size: 0x100,
+            reg_shift: 0,
+            reg_io_width: 1,
+            clock_frequency: Some(0x384000),
+            spec: Some(ConsoleSpec::new(115200)),
+        })
+    );
+}
+
+#[test]
+fn uart_early_console_board() {
+    let dtb = fdt(SERIAL_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+
+    // no options, so the line settings come from current-speed
+    let console = fdt.chosen().early_console().unwrap();
+    assert_eq!(console.kind, UartKind::Pl011);
+    assert_eq!(console.base, 0xfe20_1000);
+    assert_eq!(console.spec, Some(ConsoleSpec::new(115200)));
+
+    let dw = Uart::new(fdt.find_node("/soc@0/serial@215040").unwrap());
+    let console = dw.early_console(None).unwrap();
+    assert_eq!((console.kind, console.base, console.size), (UartKind::DwApb, 0xfe21_5040, 0x40));
+    assert_eq!((console.reg_shift, console.reg_io_width), (2, 4));
+    assert_eq!(console.clock_frequency, None);
+    assert_eq!(console.spec, None);
+
+    // 0x2000000 is outside the bus window
+    let outside = Uart::new(fdt.find_node("/soc@0/serial@2000000").unwrap());
+    assert_eq!(outside.kind(), Some(UartKind::Ns16550));
+    assert!(outside.registers().is_none());
+
+    // a bus without ranges can't be translated
+    let isolated = Uart::new(fdt.find_node("/isolated/serial@1000").unwrap());
+    assert_eq!(isolated.kind(), Some(UartKind::Sifive));
+    assert!(isolated.early_console(None).is_none());
+    assert_eq!(fdt.find_node("/isolated/serial@1000").unwrap().parent().unwrap().name, "isolated");
+    assert!(fdt.find_node("/").unwrap().parent().is_none());
+}
+
+static FRAMEBUFFER_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+
+	chosen {
+		#address-cells = <2>;
+		#size-cells = <2>;
+		ranges;
+
+		framebuffer@be000000 {
+			compatible = "simple-framebuffer";
+			reg = <0x0 0xbe000000 0x0 0x7e9000>;
+			width = <1920>;
+			height = <1080>;
+			stride = <7680>;
+			format = "a8r8g8b8";
+			clocks = <&ccu 12>, <&osc>;
+			power-domains = <&pd 3>;
+			lcd-supply = <&reg_lcd>;
+		};
+
+		framebuffer@0 {
+			compatible = "simple-framebuffer";
+			reg = <0x0 0x0 0x0 0x1000>;
+			format = "y8";
+			status = "disabled";
+		};
+	};
+
+	osc: osc {
+		#clock-cells = <0>;
+	};
+
+	ccu: clock-controller {
+		#clock-cells = <1>;
+	};
+
+	pd: power-controller {
+		#power-domain-cells = <1>;
+	};
+
+	reg_lcd: regulator-lcd {
+		compatible = "regulator-fixed";
+	};
+
+	soc {
+		#address-cells = <1>;
+		#size-cells = <1>;
+		ranges = <0x0 0x0 0x40000000 0x10000000>;
+
+		framebuffer@8000000 {
+			compatible = "simple-framebuffer";
+			reg = <0x8000000 0x200000>;
+			width = <800>;
+			height = <600>;
+			stride = <1600>;
+			format = "r5g6b5";
+		};
+	};
+};
+"#;
+
+#[test]
+fn simple_framebuffer() {
+    let dtb = fdt(FRAMEBUFFER_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+
+    let mut framebuffers = fdt.chosen().framebuffers();
+    let fb = framebuffers.next().unwrap();
+    assert!(framebuffers.next().is_none());
+
+    assert_eq!(fb.base(), Some(0xbe00_0000));
+    assert_eq!(fb.size(), Some(0x7e_9000));
+    assert_eq!((fb.width(), fb.height(), fb.stride()), (Some(1920), Some(1080), Some(7680)));
+    assert_eq!(fb.format(), Some(PixelFormat::A8R8G8B8));
+    assert_eq!(fb.format().unwrap().bits_per_pixel(), 32);
+
+    let clocks: Vec<_> = fb.clocks().map(|c| (c.provider().name, c.specifier().to_vec())).collect();
+    assert_eq!(clocks, [("clock-controller", vec![12]), ("osc", vec![])]);
+    let domains: Vec<_> = fb.power_domains().map(|d| (d.provider().name, d.specifier().to_vec())).collect();
+    assert_eq!(domains, [("power-controller", vec![3])]);
+    let regulators: Vec<_> = fb.regulators().map(|s| (s.name, s.regulator.node().name)).collect();
+    assert_eq!(regulators, [("lcd", "regulator-lcd")]);
+
+    let all: Vec<_> = fdt.simple_framebuffers().map(|fb| (fb.base(), fb.format())).collect();
+    assert_eq!(all, [(Some(0xbe00_0000), Some(PixelFormat::A8R8G8B8)), (Some(0x4800_0000), Some(PixelFormat::R5G6B5))]);
+    assert_eq!(PixelFormat::parse("y8"), None);
+}
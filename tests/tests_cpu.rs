@@ -0,0 +1,410 @@
+mod common;
+
+use common::{DTB_DATA, fdt};
+use fdtree_rs::psci::{EnableMethod, PsciMethod, PsciVersion};
+use fdtree_rs::riscv::{MmuType, RiscvIsa};
+use fdtree_rs::timer::{ArchTimerPpi, TimerIrq, TimerKind};
+use fdtree_rs::{DtsCompiler, LinuxFdt, RiscvHart};
+
+static PSCI_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+
+	psci {
+		compatible = "arm,psci-1.0", "arm,psci-0.2", "arm,psci";
+		method = "smc";
+		cpu_on = <0x84000003>;
+		cpu_off = <0x84000002>;
+		cpu_suspend = <0xc4000001>;
+		migrate = <0xc4000005>;
+	};
+
+	cpus {
+		#address-cells = <1>;
+		#size-cells = <0>;
+
+		cpu@0 {
+			device_type = "cpu";
+			compatible = "arm,cortex-a53";
+			reg = <0>;
+			enable-method = "psci";
+			cpu-idle-states = <&cpu_sleep>, <&cluster_sleep>, <&off_state>;
+		};
+
+		cpu@1 {
+			device_type = "cpu";
+			compatible = "arm,cortex-a53";
+			reg = <1>;
+			enable-method = "spin-table";
+			cpu-release-addr = <0x0 0x8000fff8>;
+		};
+
+		idle-states {
+			entry-method = "psci";
+
+			cpu_sleep: cpu-sleep {
+				compatible = "arm,idle-state";
+				idle-state-name = "cpu-sleep";
+				arm,psci-suspend-param = <0x0010000>;
+				entry-latency-us = <40>;
+				exit-latency-us = <100>;
+				min-residency-us = <150>;
+				local-timer-stop;
+			};
+
+			cluster_sleep: cluster-sleep {
+				compatible = "arm,idle-state";
+				arm,psci-suspend-param = <0x1010000>;
+				entry-latency-us = <500>;
+				exit-latency-us = <1000>;
+				wakeup-latency-us = <1200>;
+				min-residency-us = <2500>;
+			};
+
+			off_state: off {
+				compatible = "arm,idle-state";
+				status = "disabled";
+			};
+		};
+	};
+};
+"#;
+
+#[test]
+fn psci_node() {
+    let dtb = fdt(PSCI_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let psci = fdt.psci().unwrap();
+
+    assert_eq!(psci.node().name, "psci");
+    assert_eq!(psci.method(), Some(PsciMethod::Smc));
+    assert_eq!(psci.version(), Some(PsciVersion::V1_0));
+    assert_eq!(psci.cpu_on(), Some(0x8400_0003));
+    assert_eq!(psci.cpu_off(), Some(0x8400_0002));
+    assert_eq!(psci.cpu_suspend(), Some(0xc400_0001));
+    assert_eq!(psci.migrate(), Some(0xc400_0005));
+
+    let reference = LinuxFdt::new(DTB_DATA).unwrap();
+    assert!(reference.psci().is_none());
+    assert_eq!(reference.cpus().count(), 1);
+    assert_eq!(reference.cpus().next().unwrap().enable_method(), None);
+}
+
+#[test]
+fn cpu_enable_methods() {
+    let dtb = fdt(PSCI_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let cpus: Vec<_> = fdt.cpus().collect();
+
+    assert_eq!(cpus.len(), 2);
+    assert_eq!(cpus[0].node().name, "cpu@0");
+    assert_eq!(cpus[0].ids().first(), 0);
+    assert_eq!(cpus[0].enable_method(), Some(EnableMethod::Psci));
+    assert_eq!(cpus[1].enable_method(), Some(EnableMethod::SpinTable { release_addr: Some(0x8000_fff8) }));
+
+    let states: Vec<_> = cpus[0].idle_states().collect();
+    assert_eq!(states.len(), 2);
+    assert_eq!(states[0].name(), "cpu-sleep");
+    assert_eq!(states[0].psci_suspend_param(), Some(0x0001_0000));
+    assert_eq!(states[0].min_residency_us(), Some(150));
+    assert_eq!(states[0].wakeup_latency_us(), Some(140));
+    assert!(states[0].local_timer_stop());
+    assert_eq!(states[1].name(), "cluster-sleep");
+    assert_eq!(states[1].entry_latency_us(), Some(500));
+    assert_eq!(states[1].exit_latency_us(), Some(1000));
+    assert_eq!(states[1].wakeup_latency_us(), Some(1200));
+    assert!(!states[1].local_timer_stop());
+    assert_eq!(cpus[1].idle_states().count(), 0);
+}
+
+#[test]
+fn idle_states_container() {
+    let dtb = fdt(PSCI_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let idle = fdt.idle_states().unwrap();
+
+    assert_eq!(idle.node().name, "idle-states");
+    assert_eq!(idle.entry_method(), Some("psci"));
+    assert!(idle.is_psci());
+    let names: Vec<_> = idle.states().map(|state| state.name()).collect();
+    assert_eq!(names, ["cpu-sleep", "cluster-sleep"]);
+
+    let dtb = DtsCompiler::new()
+        .compile("/dts-v1/;\n/ {\n\tcpus {\n\t\tidle-states {\n\t\t\tentry-method = \"arm,vendor\";\n\t\t};\n\t};\n};")
+        .unwrap();
+    let vendor = LinuxFdt::new(&dtb).unwrap();
+    assert_eq!(vendor.idle_states().unwrap().entry_method(), Some("arm,vendor"));
+    assert!(!vendor.idle_states().unwrap().is_psci());
+    assert!(LinuxFdt::new(DTB_DATA).unwrap().idle_states().is_none());
+}
+
+static RISCV_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+
+	cpus {
+		#address-cells = <1>;
+		#size-cells = <0>;
+		timebase-frequency = <10000000>;
+
+		cpu@0 {
+			device_type = "cpu";
+			compatible = "thead,c906", "riscv";
+			reg = <0>;
+			riscv,isa = "rv64imafdc_zicsr2p0_zifencei_zicbom_svpbmt";
+			mmu-type = "riscv,sv39";
+			riscv,cbom-block-size = <64>;
+
+			cpu0_intc: interrupt-controller {
+				compatible = "riscv,cpu-intc";
+				#interrupt-cells = <1>;
+				interrupt-controller;
+			};
+		};
+
+		cpu@1 {
+			device_type = "cpu";
+			compatible = "riscv";
+			reg = <1>;
+			riscv,isa-base = "rv64i";
+			riscv,isa-extensions = "i", "m", "a", "c", "zicboz", "zicbop";
+			mmu-type = "riscv,sv57";
+			riscv,cboz-block-size = <64>;
+			riscv,cbop-block-size = <32>;
+		};
+	};
+
+	plic@c000000 {
+		compatible = "riscv,plic0";
+		reg = <0x0 0xc000000 0x0 0x4000000>;
+		interrupts-extended = <&cpu0_intc 11>, <&cpu0_intc 9>;
+	};
+};
+"#;
+
+#[test]
+fn riscv_isa_strings() {
+    let isa = RiscvIsa::parse("rv64i2p1m_zicsr2p0_Zba1").unwrap();
+    assert_eq!(isa.bits(), 64);
+    assert_eq!(isa.extensions().collect::<Vec<_>>(), ["i", "m", "zicsr", "Zba"]);
+    assert!(isa.has("zba"));
+
+    let isa = RiscvIsa::parse("rv32gc").unwrap();
+    assert_eq!(isa.bits(), 32);
+    assert_eq!(isa.extensions().collect::<Vec<_>>(), ["i", "m", "a", "f", "d", "zicsr", "zifencei", "c"]);
+
+    let isa = RiscvIsa::parse("rv64imafdcsu").unwrap();
+    assert_eq!(isa.extensions().collect::<Vec<_>>(), ["i", "m", "a", "f", "d", "c"]);
+    assert!(RiscvIsa::parse("rv64imac_sstc").unwrap().has("sstc"));
+    assert!(RiscvIsa::parse("x86").is_none());
+}
+
+#[test]
+fn riscv_harts() {
+    let dtb = fdt(RISCV_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let harts: Vec<_> = fdt.cpus().filter_map(RiscvHart::new).collect();
+    assert_eq!(harts.len(), 2);
+
+    assert_eq!(harts[0].hart_id(), Some(0));
+    assert!(harts[0].has("zicbom"));
+    assert!(harts[0].has("zicsr"));
+    assert!(harts[0].has("svpbmt"));
+    assert!(!harts[0].has("zicboz"));
+    assert_eq!(harts[0].mmu_type(), Some(MmuType::Sv39));
+    assert_eq!(harts[0].cbom_block_size(), Some(64));
+    assert_eq!(harts[0].cboz_block_size(), None);
+
+    assert_eq!(harts[1].hart_id(), Some(1));
+    assert_eq!(harts[1].isa().unwrap().bits(), 64);
+    assert_eq!(harts[1].isa().unwrap().extensions().count(), 6);
+    assert!(harts[1].has("zicboz"));
+    assert_eq!(harts[1].mmu_type(), Some(MmuType::Sv57));
+    assert_eq!((harts[1].cboz_block_size(), harts[1].cbop_block_size()), (Some(64), Some(32)));
+    assert!(harts[1].interrupt_controller().is_none());
+
+    let intc = harts[0].interrupt_controller().unwrap();
+    assert_eq!(intc.name, "interrupt-controller");
+    let plic = fdt.find_node("/plic@c000000").unwrap();
+    let context = plic.phandle_args("interrupts-extended", "#interrupt-cells").next().unwrap();
+    assert_eq!(RiscvHart::from_intc(context.node).unwrap().hart_id(), Some(0));
+}
+
+#[test]
+fn riscv_reference_blob() {
+    let fdt = LinuxFdt::new(DTB_DATA).unwrap();
+    let hart = fdt.cpus().find_map(RiscvHart::new).unwrap();
+    assert_eq!(hart.hart_id(), Some(0));
+    assert_eq!(hart.mmu_type(), Some(MmuType::Sv48));
+    assert_eq!(hart.isa().unwrap().extensions().collect::<Vec<_>>(), ["i", "m", "a", "f", "d", "c"]);
+    assert!(hart.interrupt_controller().is_some());
+}
+
+static TIMER_ARM: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+	interrupt-parent = <&gic>;
+
+	gic: interrupt-controller@2c001000 {
+		compatible = "arm,gic-400";
+		reg = <0x2c001000 0x1000>;
+		#interrupt-cells = <3>;
+		interrupt-controller;
+	};
+
+	timer {
+		compatible = "arm,armv8-timer";
+		interrupts = <1 13 0xf08>, <1 14 0xf08>, <1 11 0xf08>, <1 10 0xf08>;
+		clock-frequency = <24000000>;
+		always-on;
+		arm,no-tick-in-suspend;
+	};
+
+	soc {
+		#address-cells = <1>;
+		#size-cells = <1>;
+		ranges = <0x0 0x80000000 0x40000000>;
+
+		timer@2a810000 {
+			compatible = "arm,armv7-timer-mem";
+			reg = <0x2a810000 0x10000>;
+			#address-cells = <1>;
+			#size-cells = <1>;
+			ranges;
+
+			frame@2a820000 {
+				frame-number = <0>;
+				interrupts = <0 25 4>, <0 26 4>;
+				reg = <0x2a820000 0x10000>, <0x2a830000 0x10000>;
+			};
+
+			frame@2a840000 {
+				frame-number = <1>;
+				interrupts = <0 27 4>;
+				reg = <0x2a840000 0x10000>;
+			};
+
+			frame@2a850000 {
+				frame-number = <2>;
+				reg = <0x2a850000 0x10000>;
+				status = "disabled";
+			};
+		};
+	};
+};
+"#;
+
+static TIMER_ARM_NAMED: &str = r#"
+/dts-v1/;
+
+/ {
+	gic: interrupt-controller {
+		#interrupt-cells = <3>;
+		interrupt-controller;
+	};
+
+	timer {
+		compatible = "arm,armv7-timer";
+		interrupt-parent = <&gic>;
+		interrupts = <1 10 0x304>, <1 11 0x304>;
+		interrupt-names = "hyp-phys", "virt";
+	};
+};
+"#;
+
+#[test]
+fn arm_arch_timer() {
+    let dtb = fdt(TIMER_ARM);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let timer = fdt.timer().unwrap();
+
+    assert_eq!(timer.kind, TimerKind::Armv8);
+    assert_eq!(timer.frequency, Some(24_000_000));
+    assert!(timer.always_on);
+    assert!(timer.no_tick_in_suspend);
+    assert_eq!(timer.ppi(ArchTimerPpi::SecurePhys), Some(TimerIrq { ppi: true, number: 13, flags: 0xf08 }));
+    assert_eq!(timer.ppi(ArchTimerPpi::Virt).unwrap().number, 11);
+    assert_eq!(timer.ppi(ArchTimerPpi::HypPhys).unwrap().number, 10);
+    assert_eq!(timer.ppi(ArchTimerPpi::HypVirt), None);
+}
+
+#[test]
+fn arm_arch_timer_named_interrupts() {
+    let dtb = fdt(TIMER_ARM_NAMED);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let timer = fdt.timer().unwrap();
+    assert_eq!(timer.kind, TimerKind::Armv7);
+    assert_eq!(timer.frequency, None);
+    assert!(!timer.always_on);
+    assert_eq!(timer.ppi(ArchTimerPpi::Phys), None);
+    assert_eq!(timer.ppi(ArchTimerPpi::HypPhys).unwrap().number, 10);
+    assert_eq!(timer.ppi(ArchTimerPpi::Virt).unwrap().number, 11);
+}
+
+#[test]
+fn arm_timer_mem() {
+    let dtb = fdt(TIMER_ARM);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let timer = fdt.timer_mem().unwrap();
+
+    assert_eq!(timer.node().name, "timer@2a810000");
+    assert_eq!(timer.control_base(), Some(0xaa81_0000));
+    assert_eq!(timer.frequency(), None);
+
+    let frames: Vec<_> = timer.frames().collect();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].number, 0);
+    assert_eq!(frames[0].base, 0xaa82_0000);
+    assert_eq!(frames[0].el0_base, Some(0xaa83_0000));
+    assert_eq!(frames[0].phys_irq, Some(TimerIrq { ppi: false, number: 25, flags: 4 }));
+    assert_eq!(frames[0].virt_irq.unwrap().number, 26);
+    assert_eq!(frames[1].el0_base, None);
+    assert_eq!(frames[1].virt_irq, None);
+}
+
+#[test]
+fn riscv_timer() {
+    let fdt = LinuxFdt::new(DTB_DATA).unwrap();
+    let timer = fdt.timer().unwrap();
+
+    assert_eq!(timer.kind, TimerKind::Riscv);
+    assert_eq!(timer.frequency, Some(10_000_000));
+    assert_eq!(timer.ppis, [None; 5]);
+    assert!(timer.always_on);
+    assert!(fdt.timer_mem().is_none());
+}
+
+// an ARM board without an arch timer node, carrying a stray timebase
+static TIMEBASE_ARM: &str = r#"
+/dts-v1/;
+
+/ {
+	cpus {
+		#address-cells = <1>;
+		#size-cells = <0>;
+		timebase-frequency = <24000000>;
+
+		cpu@0 {
+			device_type = "cpu";
+			compatible = "arm,cortex-a53";
+			reg = <0>;
+		};
+	};
+};
+"#;
+
+#[test]
+fn timebase_without_riscv_harts() {
+    let dtb = fdt(TIMEBASE_ARM);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    assert!(fdt.timer().is_none());
+}
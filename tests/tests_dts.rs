@@ -0,0 +1,222 @@
+mod common;
+
+use common::{DTB_DATA, fdt};
+use fdtree_rs::{DtsCompiler, DtsWriter, Indent, LinuxFdt};
+
+fn setup() -> LinuxFdt<'static> {
+    LinuxFdt::new(DTB_DATA).unwrap()
+}
+
+fn dts(writer: DtsWriter) -> String {
+    let mut out = String::new();
+    writer.write(&setup(), &mut out).unwrap();
+    out
+}
+
+#[test]
+fn dts_header_and_memreserve() {
+    let out = dts(DtsWriter::new());
+    let mut lines = out.lines();
+    assert_eq!(lines.next(), Some("/dts-v1/;"));
+    assert_eq!(lines.next(), Some(""));
+    assert_eq!(lines.next(), Some("/memreserve/ 0x0000000080000000 0x0000000001000000;"));
+    assert_eq!(lines.next(), Some("/memreserve/ 0x0000000090000000 0x0000000000100000;"));
+    assert_eq!(lines.next(), Some("/ {"));
+    assert_eq!(out.lines().last(), Some("};"));
+}
+
+#[test]
+fn dts_property_values() {
+    let out = dts(DtsWriter::new());
+    assert!(out.contains("\tmodel = \"riscv-virtio,qemu\";\n"));
+    assert!(out.contains("\t\tcompatible = \"sifive,test1\", \"sifive,test0\", \"syscon\";\n"));
+    assert!(out.contains("\t\treg = <0x00 0x30000000 0x00 0x10000000>;\n"));
+    assert!(out.contains("\t\tclock-frequency = <0x384000>;\n"));
+    assert!(out.contains("\t\tdma-coherent;\n"));
+    assert!(out.contains("\t\tinterrupt-parent = <&{/soc/plic@c000000}>;\n"));
+}
+
+#[test]
+fn dts_indent() {
+    let out = dts(DtsWriter::new().indent(Indent::Spaces(2)));
+    assert!(out.contains("\n  chosen {\n    bootargs = \"console=ttyS0\";\n"));
+    assert!(!out.contains('\t'));
+    assert_eq!(out, format!("{:?}", setup()).replace('\t', "  "));
+}
+
+static DTS_DATA: &str = include_str!("../dts/test.dts");
+
+static DTC_SOURCE: &str = r#"
+/dts-v1/;
+
+/memreserve/ 0x1000 (4 * 1024);
+
+/include/ "board.dtsi"
+
+/ {
+	model = "test,board";
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	intc: interrupt-controller@1000 {
+		reg = <0x1000 0x100>;
+		#interrupt-cells = <1>;
+		interrupt-controller;
+	};
+
+	uart0: serial@2000 {
+		reg = <0x2000 0x100>;
+		interrupt-parent = <&intc>;
+		interrupts = <(3 + 2) 'a'>;
+		clocks = <&clk 1>, <&{/clocks/osc}>;
+		mac = [00 11 22 33 44 55];
+		widths = /bits/ 16 <0x1234 (-1)>, /bits/ 8 <0xff>;
+		names = "a", "b";
+		removed;
+	};
+
+	/omit-if-no-ref/ unused {
+		dropped;
+	};
+
+	/omit-if-no-ref/ used: kept {
+	};
+};
+
+&uart0 {
+	/delete-property/ removed;
+	status = "okay";
+	target = &used;
+};
+
+/delete-node/ &gone;
+"#;
+
+static DTC_BOARD: &str = r#"
+/ {
+	aliases {
+		serial0 = &uart0;
+	};
+
+	clocks {
+		clk: fixed {
+			#clock-cells = <1>;
+		};
+
+		osc {
+		};
+	};
+
+	gone: gone {
+	};
+};
+"#;
+
+fn compile(symbols: bool) -> Vec<u8> {
+    let mut resolver = |path: &str| (path == "board.dtsi").then(|| String::from(DTC_BOARD));
+    DtsCompiler::new()
+        .symbols(symbols)
+        .local_fixups(symbols)
+        .include_resolver(&mut resolver)
+        .compile(DTC_SOURCE)
+        .unwrap()
+}
+
+#[test]
+fn dtc_matches_reference_blob() {
+    let dtb = fdt(DTS_DATA);
+    assert_eq!(dtb, DTB_DATA);
+}
+
+#[test]
+fn dtc_source_features() {
+    let dtb = compile(false);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+
+    let rsv = fdt.sys_memory_reservations().next().unwrap();
+    assert_eq!(rsv.address() as usize, 0x1000);
+    assert_eq!(rsv.size(), 0x1000);
+
+    let uart = fdt.find_node("/serial@2000").unwrap();
+    assert_eq!(uart.interrupt_parent().unwrap().name, "interrupt-controller@1000");
+    assert_eq!(uart.property("interrupts").unwrap().value, [0, 0, 0, 5, 0, 0, 0, b'a']);
+    assert_eq!(uart.property("mac").unwrap().value, [0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+    assert_eq!(uart.property("widths").unwrap().value, [0x12, 0x34, 0xff, 0xff, 0xff]);
+    assert_eq!(uart.property("names").unwrap().value, b"a\0b\0");
+    assert_eq!(uart.property("status").unwrap().as_str(), Some("okay"));
+    assert_eq!(uart.property("target").unwrap().as_str(), Some("/kept"));
+    assert!(uart.property("removed").is_none());
+
+    // referenced nodes without a phandle get one
+    let clocks = uart.property("clocks").unwrap().value;
+    let fixed = fdt.find_node("/clocks/fixed").unwrap();
+    let osc = fdt.find_node("/clocks/osc").unwrap();
+    let phandle = |node: fdtree_rs::FdtNode| node.property("phandle").unwrap().value.to_vec();
+    assert_eq!(clocks[..4], phandle(fixed)[..]);
+    assert_eq!(clocks[4..8], [0, 0, 0, 1]);
+    assert_eq!(clocks[8..], phandle(osc)[..]);
+    assert_ne!(phandle(fixed), phandle(osc));
+
+    assert_eq!(fdt.aliases().unwrap().resolve("serial0"), Some("/serial@2000"));
+    assert!(fdt.find_node("/unused").is_none());
+    assert!(fdt.find_node("/kept").is_some());
+    assert!(fdt.find_node("/gone").is_none());
+}
+
+#[test]
+fn dtc_symbols_round_trip() {
+    let dtb = compile(true);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    assert_eq!(
+        fdt.find_node("/__symbols__").unwrap().property("uart0").unwrap().as_str(),
+        Some("/serial@2000")
+    );
+
+    let dts = format!("{:?}", fdt);
+    assert!(dts.contains("\tuart0: serial@2000 {\n"));
+    assert!(dts.contains("\t\tinterrupt-parent = <&intc>;\n"));
+    assert!(dts.contains("\t\tclocks = <&clk 0x01 &{/clocks/osc}>;\n"));
+
+    let again = DtsCompiler::new().compile(&dts).unwrap();
+    assert_eq!(again, dtb);
+
+    let mut out = String::new();
+    DtsWriter::new().write(&LinuxFdt::new(DTB_DATA).unwrap(), &mut out).unwrap();
+    assert_eq!(DtsCompiler::new().compile(&out).unwrap(), DTB_DATA);
+}
+
+#[test]
+fn structure_block_ends_with_fdt_end() {
+    // FDT_END is token 9; the parser used to define it as 5
+    let dtb = fdt("/dts-v1/;\n/ {\n\tnode {\n\t};\n};\n");
+    let word = |offset: usize| u32::from_be_bytes(dtb[offset..offset + 4].try_into().unwrap()) as usize;
+    let (off_dt_struct, size_dt_struct) = (word(8), word(36));
+    assert_eq!(word(off_dt_struct + size_dt_struct - 4), 9);
+
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    assert_eq!(fdt.all_nodes().map(|node| node.name).collect::<Vec<_>>(), ["/", "node"]);
+}
+
+#[test]
+fn legacy_linux_phandle() {
+    let src = "/dts-v1/;\n/ {\n\tintc: intc {\n\t\tlinux,phandle = <7>;\n\t};\n\tdev {\n\t\tirq = <&intc>;\n\t};\n};\n";
+    let dtb = fdt(src);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let intc = fdt.find_node("/intc").unwrap();
+    assert!(intc.property("phandle").is_none());
+    assert_eq!(fdt.find_node("/dev").unwrap().property("irq").unwrap().value, [0, 0, 0, 7]);
+    assert_eq!(fdt.find_phandle(7).unwrap().name, "intc");
+}
+
+#[test]
+fn dtc_errors() {
+    let err = DtsCompiler::new().compile("/dts-v1/;\n/ {\n\tfoo = <&missing>;\n};\n").unwrap_err();
+    assert_eq!((err.line, err.column), (3, 9));
+    assert_eq!(err.message, "reference to non-existent node or label");
+
+    let err = DtsCompiler::new().compile("/ { };").unwrap_err();
+    assert_eq!(err.message, "missing /dts-v1/ tag");
+
+    let err = DtsCompiler::new().compile("/dts-v1/;\n/ {\n\tfoo = /bits/ 8 <256>;\n};\n").unwrap_err();
+    assert_eq!(err.to_string(), "<source>:3:18: value out of range for 8-bit array element");
+}
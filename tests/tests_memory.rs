@@ -0,0 +1,576 @@
+mod common;
+
+use common::{DTB_DATA, fdt};
+use fdtree_rs::reserved_memory::{AllocPolicy, DynamicAllocator, ReservedMemoryHandler, ReservedRegion};
+use fdtree_rs::{
+    FdtError, LinuxFdt, LinuxFdtMut, MemoryKind, MemoryMap, MemoryRange, MemoryReservation, ReservedMemoryError,
+};
+
+fn buffer(spare: usize) -> Vec<u8> {
+    let mut buf = DTB_DATA.to_vec();
+    buf.resize(DTB_DATA.len() + spare, 0);
+    buf
+}
+
+#[test]
+fn mem_rsv_accessors() {
+    let rsv = MemoryReservation::new(0x1_8000_0000, 0x2000_0000);
+    assert_eq!(rsv.start(), 0x1_8000_0000);
+    assert_eq!(rsv.end(), 0x1_a000_0000);
+    assert_eq!(rsv.size(), 0x2000_0000);
+    assert_eq!(core::ops::Range::from(rsv), 0x1_8000_0000..0x1_a000_0000);
+    assert_eq!(MemoryReservation::new(u64::MAX - 1, 16).end(), u64::MAX);
+}
+
+#[test]
+fn mem_rsv_add_and_delete() {
+    let mut buf = buffer(64);
+    let mut fdt = LinuxFdtMut::new(&mut buf).unwrap();
+    assert_eq!(fdt.num_mem_rsv(), 2);
+
+    fdt.add_mem_rsv(0x1_0000_0000, 0x80_0000).unwrap();
+    fdt.add_mem_rsv(0x8020_0000, 0x1000).unwrap();
+    assert_eq!(fdt.total_size(), DTB_DATA.len() + 32);
+
+    let ranges: Vec<_> = fdt.as_fdt().sys_memory_reservations().map(|r| r.range()).collect();
+    assert_eq!(
+        ranges,
+        [
+            0x8000_0000..0x8100_0000,
+            0x9000_0000..0x9010_0000,
+            0x1_0000_0000..0x1_0080_0000,
+            0x8020_0000..0x8020_1000,
+        ]
+    );
+
+    // the rest of the tree moved along
+    assert_eq!(fdt.as_fdt().machine(), "riscv-virtio,qemu");
+    assert_eq!(fdt.as_fdt().chosen().bootargs(), Some("console=ttyS0"));
+
+    let removed = fdt.del_mem_rsv(0).unwrap();
+    assert_eq!(removed.start(), 0x8000_0000);
+    assert_eq!(fdt.del_mem_rsv(3), Err(FdtError::NotFound));
+    assert_eq!(fdt.num_mem_rsv(), 3);
+    assert_eq!(fdt.as_fdt().sys_memory_reservations().next().unwrap().start(), 0x9000_0000);
+    assert_eq!(fdt.as_fdt().root().model(), "riscv-virtio,qemu");
+}
+
+#[test]
+fn mem_rsv_no_space() {
+    let mut buf = buffer(8);
+    let mut fdt = LinuxFdtMut::new(&mut buf).unwrap();
+    assert_eq!(fdt.add_mem_rsv(0x1000, 0x1000), Err(FdtError::NoSpace));
+    assert_eq!(fdt.num_mem_rsv(), 2);
+    assert_eq!(fdt.total_size(), DTB_DATA.len());
+}
+
+#[test]
+fn mem_rsv_zero_size() {
+    let mut buf = buffer(64);
+    let mut fdt = LinuxFdtMut::new(&mut buf).unwrap();
+    assert_eq!(fdt.add_mem_rsv(0x1000, 0), Err(FdtError::BadValue));
+    assert_eq!(fdt.total_size(), DTB_DATA.len());
+
+    fdt.add_mem_rsv(0x1000, 0x1000).unwrap();
+    assert_eq!(fdt.num_mem_rsv(), 3);
+}
+
+static MEMORY_MAP_BOARD: &str = r#"
+/dts-v1/;
+
+/memreserve/ 0x80000000 0x1000;
+/memreserve/ 0x1f0000000 0x1000;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+
+	chosen {
+	};
+
+	memory@80000000 {
+		device_type = "memory";
+		reg = <0x0 0x80000000 0x0 0x40000000>;
+	};
+
+	memory@c0000000 {
+		device_type = "memory";
+		reg = <0x0 0xc0000000 0x0 0x40000000>, <0x1 0x00000000 0x0 0x10000000>;
+	};
+
+	memory@200000000 {
+		device_type = "memory";
+		reg = <0x2 0x0 0x0 0x1000000>;
+		status = "disabled";
+	};
+
+	reserved-memory {
+		#address-cells = <2>;
+		#size-cells = <2>;
+		ranges;
+
+		opensbi@80000000 {
+			reg = <0x0 0x80000000 0x0 0x200000>;
+			no-map;
+		};
+
+		fw@ffff0000 {
+			reg = <0x0 0xffff0000 0x0 0x20000>;
+		};
+
+		outside@1f0000000 {
+			reg = <0x1 0xf0000000 0x0 0x100000>;
+			no-map;
+		};
+
+		pool {
+			size = <0x0 0x100000>;
+		};
+	};
+};
+"#;
+
+fn range(start: u64, end: u64, kind: MemoryKind) -> MemoryRange {
+    MemoryRange { start, size: end - start, kind }
+}
+
+#[test]
+fn memory_map_from_fdt() {
+    let dtb = fdt(MEMORY_MAP_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let mut storage = [MemoryRange::default(); 16];
+    let map = fdt.memory_map(&mut storage).unwrap();
+
+    assert_eq!(
+        map.ranges(),
+        [
+            range(0x8000_0000, 0x8020_0000, MemoryKind::NoMap),
+            range(0x8020_0000, 0xffff_0000, MemoryKind::Usable),
+            range(0xffff_0000, 0x1_0001_0000, MemoryKind::Reserved),
+            range(0x1_0001_0000, 0x1_1000_0000, MemoryKind::Usable),
+            range(0x1_f000_0000, 0x1_f000_1000, MemoryKind::Reserved),
+        ]
+    );
+    assert_eq!(map.usable().count(), 2);
+    assert_eq!(map.kind_at(0x8000_0800), Some(MemoryKind::NoMap));
+    assert_eq!(map.kind_at(0x1_f000_2000), None);
+}
+
+#[test]
+fn memory_map_without_chosen() {
+    let dtb = fdt(&MEMORY_MAP_BOARD.replace("\tchosen {\n\t};\n", ""));
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    assert!(fdt.find_node("/chosen").is_none());
+
+    let mut storage = [MemoryRange::default(); 16];
+    let map = fdt.memory_map(&mut storage).unwrap();
+    assert_eq!(map.usable().count(), 2);
+    assert_eq!(map.kind_at(0xffff_0000), Some(MemoryKind::Reserved));
+}
+
+#[test]
+fn memory_map_usable_memory_range() {
+    let fdt = LinuxFdt::new(DTB_DATA).unwrap();
+    let mut storage = [MemoryRange::default(); 8];
+    let map = fdt.memory_map(&mut storage).unwrap();
+
+    // the first usable range doesn't overlap memory, so only the second
+    // range and the reservations are left
+    assert_eq!(
+        map.ranges(),
+        [
+            range(0x8000_0000, 0x8200_0000, MemoryKind::Reserved),
+            range(0x9000_0000, 0x9010_0000, MemoryKind::Reserved),
+            range(0xa_0000_0000, 0xa_2000_0000, MemoryKind::Usable),
+        ]
+    );
+}
+
+#[test]
+fn memory_map_manual() {
+    let mut storage = [MemoryRange::default(); 4];
+    let mut map = MemoryMap::new(&mut storage);
+    map.add_memory(0x1000, 0x1000).unwrap();
+    map.add_memory(0x2000, 0x2000).unwrap();
+    map.reserve(0x1800, 0x100).unwrap();
+    map.mark_no_map(0x3000, 0x2000).unwrap();
+    assert_eq!(
+        map.ranges(),
+        [
+            range(0x1000, 0x1800, MemoryKind::Usable),
+            range(0x1800, 0x1900, MemoryKind::Reserved),
+            range(0x1900, 0x3000, MemoryKind::Usable),
+            range(0x3000, 0x4000, MemoryKind::NoMap),
+        ]
+    );
+
+    // a reservation can't weaken no-map, and a full storage leaves the map as it was
+    assert_eq!(map.reserve(0x3800, 0x100), Ok(()));
+    assert_eq!(map.reserve(0x2000, 0x100), Err(FdtError::NoSpace));
+    assert_eq!(map.ranges().len(), 4);
+
+    map.cap(0x1900, 0x2000);
+    assert_eq!(
+        map.ranges(),
+        [range(0x1900, 0x3000, MemoryKind::Usable), range(0x3000, 0x3900, MemoryKind::NoMap)]
+    );
+}
+
+#[test]
+fn memory_range_end_saturates() {
+    let range = MemoryRange { start: u64::MAX - 0xfff, size: 0x2000, kind: MemoryKind::Usable };
+    assert_eq!(range.end(), u64::MAX);
+}
+
+static RESERVED_ALLOC_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+
+	chosen {
+	};
+
+	memory@80000000 {
+		device_type = "memory";
+		reg = <0x0 0x80000000 0x0 0x10000000>;
+	};
+
+	reserved-memory {
+		#address-cells = <2>;
+		#size-cells = <2>;
+		ranges;
+
+		fw@8ff00000 {
+			reg = <0x0 0x8ff00000 0x0 0x100000>;
+		};
+
+		pool_a {
+			size = <0x0 0x100000>;
+			alignment = <0x0 0x100000>;
+		};
+
+		linux,cma {
+			compatible = "shared-dma-pool";
+			reusable;
+			size = <0x0 0x400000>;
+		};
+
+		ranged {
+			size = <0x0 0x1000>;
+			alloc-ranges = <0x0 0x80000000 0x0 0x1000000>;
+			no-map;
+		};
+
+		too_big {
+			size = <0x0 0x20000000>;
+		};
+
+		bad_align {
+			size = <0x0 0x1000>;
+			alignment = <0x0 0x3000>;
+		};
+	};
+};
+"#;
+
+fn allocate(allocator: DynamicAllocator) -> Vec<(String, Result<MemoryRange, ReservedMemoryError>)> {
+    let dtb = fdt(RESERVED_ALLOC_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let mut storage = [MemoryRange::default(); 16];
+    let mut map = fdt.memory_map(&mut storage).unwrap();
+    let reserved = fdt.linux_reserved_memory().unwrap();
+
+    let results: Vec<_> = allocator
+        .allocate_all(reserved, &mut map)
+        .map(|a| (String::from(a.node.node.name), a.result))
+        .collect();
+
+    // every placed region is taken out of the usable memory
+    for (_, result) in &results {
+        if let Ok(range) = result {
+            assert_eq!(map.kind_at(range.start), Some(range.kind));
+            assert_eq!(map.kind_at(range.end() - 1), Some(range.kind));
+        }
+    }
+    results
+}
+
+fn placed(start: u64, size: u64, kind: MemoryKind) -> Result<MemoryRange, ReservedMemoryError> {
+    Ok(MemoryRange { start, size, kind })
+}
+
+#[test]
+fn reserved_alloc_top_down() {
+    let results = allocate(DynamicAllocator::new());
+    let names: Vec<_> = results.iter().map(|(name, _)| name.as_str()).collect();
+    // bad_align is rejected as invalid before it gets to the allocator
+    assert_eq!(names, ["pool_a", "linux,cma", "ranged", "too_big"]);
+
+    assert_eq!(results[0].1, placed(0x8fe0_0000, 0x10_0000, MemoryKind::Reserved));
+    // reusable shared-dma-pool gets CMA alignment
+    assert_eq!(results[1].1, placed(0x8fa0_0000, 0x40_0000, MemoryKind::Reserved));
+    assert_eq!(results[2].1, placed(0x80ff_f000, 0x1000, MemoryKind::NoMap));
+    assert_eq!(results[3].1, Err(ReservedMemoryError::NoMemory));
+}
+
+#[test]
+fn reserved_alloc_bottom_up() {
+    let results = allocate(DynamicAllocator::new().policy(AllocPolicy::BottomUp));
+    assert_eq!(results[0].1, placed(0x8000_0000, 0x10_0000, MemoryKind::Reserved));
+    assert_eq!(results[1].1, placed(0x8020_0000, 0x40_0000, MemoryKind::Reserved));
+    assert_eq!(results[2].1, placed(0x8010_0000, 0x1000, MemoryKind::NoMap));
+    assert_eq!(results[3].1, Err(ReservedMemoryError::NoMemory));
+}
+
+static RESERVED_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+
+	chosen {
+	};
+
+	reserved-memory {
+		#address-cells = <2>;
+		#size-cells = <2>;
+		ranges;
+
+		cma: linux,cma {
+			compatible = "shared-dma-pool";
+			reusable;
+			size = <0x1 0x0>;
+			linux,cma-default;
+		};
+
+		dma: dma-pool@90000000 {
+			compatible = "shared-dma-pool";
+			reg = <0x0 0x90000000 0x0 0x100000>;
+			no-map;
+			linux,dma-default;
+		};
+
+		swiotlb: restricted@91000000 {
+			compatible = "restricted-dma-pool";
+			reg = <0x0 0x91000000 0x0 0x400000>;
+		};
+
+		ramoops@92000000 {
+			compatible = "ramoops";
+			reg = <0x0 0x92000000 0x0 0x100000>;
+			record-size = <0x4000>;
+			console-size = <0x8000>;
+			pmsg-size = <0x2000>;
+			max-reason = <2>;
+			unbuffered;
+		};
+
+		vendor: secure@93000000 {
+			compatible = "vendor,secure-heap", "vendor,heap";
+			reg = <0x0 0x93000000 0x0 0x1000>;
+		};
+
+		off@94000000 {
+			reg = <0x0 0x94000000 0x0 0x1000>;
+			status = "disabled";
+		};
+	};
+
+	dev@10000000 {
+		reg = <0x0 0x10000000 0x0 0x1000>;
+		memory-region = <&swiotlb &cma>;
+		memory-region-names = "restricted", "cma";
+	};
+
+	other@20000000 {
+		reg = <0x0 0x20000000 0x0 0x1000>;
+		memory-region = <&vendor>;
+	};
+
+	broken@30000000 {
+		reg = <0x0 0x30000000 0x0 0x1000>;
+		memory-region = <&vendor 0xdead &cma>;
+		memory-region-names = "vendor", "bad", "cma";
+	};
+};
+"#;
+
+#[test]
+fn reserved_memory_handlers() {
+    let dtb = fdt(RESERVED_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let reserved = fdt.linux_reserved_memory().unwrap();
+
+    let handlers: Vec<_> = reserved.nodes().map(|n| n.handler()).collect();
+    assert_eq!(
+        handlers,
+        [
+            Some(ReservedMemoryHandler::Cma),
+            Some(ReservedMemoryHandler::DmaPool),
+            Some(ReservedMemoryHandler::RestrictedDmaPool),
+            Some(ReservedMemoryHandler::Ramoops),
+            Some(ReservedMemoryHandler::Other("vendor,secure-heap")),
+        ]
+    );
+
+    // the size is read with the parent's #size-cells
+    let cma = reserved.cma_default().unwrap();
+    assert_eq!(cma.node.name, "linux,cma");
+    let ReservedRegion::Dynamic(dynamic) = cma.region() else { panic!("expected a dynamic region") };
+    assert_eq!(dynamic.size(), Ok(0x1_0000_0000));
+
+    let dma = reserved.dma_default().unwrap();
+    assert_eq!(dma.node.name, "dma-pool@90000000");
+    let ReservedRegion::Static(region) = dma.region() else { panic!("expected a static region") };
+    assert!(region.nomap());
+
+    let ramoops = reserved.nodes().find_map(|n| n.ramoops()).unwrap();
+    assert_eq!(ramoops.record_size(), 0x4000);
+    assert_eq!(ramoops.console_size(), 0x8000);
+    assert_eq!(ramoops.ftrace_size(), 0);
+    assert_eq!(ramoops.pmsg_size(), 0x2000);
+    assert_eq!(ramoops.max_reason(), Some(2));
+    assert!(ramoops.unbuffered());
+    assert!(cma.ramoops().is_none());
+}
+
+#[test]
+fn reserved_memory_references() {
+    let dtb = fdt(RESERVED_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+
+    let dev = fdt.find_node("/dev@10000000").unwrap();
+    let names: Vec<_> = dev.memory_regions().map(|(i, r)| (i, r.node.name)).collect();
+    assert_eq!(names, [(0, "restricted@91000000"), (1, "linux,cma")]);
+    assert_eq!(dev.memory_region("cma").unwrap().handler(), Some(ReservedMemoryHandler::Cma));
+    assert_eq!(dev.memory_region("restricted").unwrap().node.name, "restricted@91000000");
+    assert!(dev.memory_region("missing").is_none());
+
+    let other = fdt.find_node("/other@20000000").unwrap();
+    assert_eq!(other.memory_regions().count(), 1);
+    assert!(other.memory_region("vendor").is_none());
+    assert_eq!(fdt.find_node("/chosen").unwrap().memory_regions().count(), 0);
+
+    // an unresolved phandle doesn't shift the names of the entries after it
+    let broken = fdt.find_node("/broken@30000000").unwrap();
+    let indices: Vec<_> = broken.memory_regions().map(|(i, _)| i).collect();
+    assert_eq!(indices, [0, 2]);
+    assert!(broken.memory_region("bad").is_none());
+    assert_eq!(broken.memory_region("cma").unwrap().node.name, "linux,cma");
+}
+
+static RESERVED_MALFORMED: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+
+	chosen {
+	};
+
+	memory@80000000 {
+		device_type = "memory";
+		reg = <0x0 0x80000000 0x0 0x10000000>;
+	};
+
+	reserved-memory {
+		#address-cells = <2>;
+		#size-cells = <2>;
+		ranges;
+
+		good@80000000 {
+			reg = <0x0 0x80000000 0x0 0x1000>;
+		};
+
+		short-reg@81000000 {
+			reg = <0x81000000 0x1000>;
+		};
+
+		empty {
+		};
+
+		short-size {
+			size = <0x1000>;
+		};
+
+		bad-align {
+			size = <0x0 0x1000>;
+			alignment = <0x1000>;
+		};
+
+		odd-align {
+			size = <0x0 0x1000>;
+			alignment = <0x0 0x1800>;
+		};
+
+		bad-ranges {
+			size = <0x0 0x1000>;
+			alloc-ranges = <0x0 0x80000000>;
+		};
+	};
+};
+"#;
+
+#[test]
+fn reserved_memory_invalid_nodes() {
+    let dtb = fdt(RESERVED_MALFORMED);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let reserved = fdt.linux_reserved_memory().unwrap();
+    assert_eq!(reserved.check(), Ok(()));
+
+    let kinds: Vec<_> = reserved
+        .regions()
+        .map(|r| match r {
+            ReservedRegion::Static(_) => "static",
+            ReservedRegion::Dynamic(_) => "dynamic",
+            ReservedRegion::Invalid(_) => "invalid",
+        })
+        .collect();
+    assert_eq!(kinds, ["static", "invalid", "invalid", "invalid", "invalid", "invalid", "invalid"]);
+
+    let invalid: Vec<_> = reserved.invalid_nodes().map(|n| (n.node.name, n.reason)).collect();
+    assert_eq!(
+        invalid,
+        [
+            ("short-reg@81000000", ReservedMemoryError::BadReg),
+            ("empty", ReservedMemoryError::MissingRegOrSize),
+            ("short-size", ReservedMemoryError::BadSize),
+            ("bad-align", ReservedMemoryError::BadAlignment),
+            ("odd-align", ReservedMemoryError::BadAlignment),
+            ("bad-ranges", ReservedMemoryError::BadAllocRanges),
+        ]
+    );
+
+    // malformed size-only nodes never reach the allocator
+    assert_eq!(reserved.dynamic_nodes().count(), 0);
+    let mut storage = [MemoryRange::default(); 8];
+    let mut map = fdt.memory_map(&mut storage).unwrap();
+    assert_eq!(DynamicAllocator::new().allocate_all(reserved, &mut map).count(), 0);
+}
+
+#[test]
+fn reserved_memory_bad_root() {
+    let source = RESERVED_MALFORMED.replacen("\t\tranges;\n", "", 1);
+    let dtb = fdt(&source);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let reserved = fdt.linux_reserved_memory().unwrap();
+
+    assert_eq!(reserved.check(), Err(ReservedMemoryError::MissingRanges));
+    assert_eq!(reserved.valid_reserved_nodes().count(), 0);
+    assert_eq!(reserved.dynamic_nodes().count(), 0);
+    assert!(reserved.invalid_nodes().all(|n| n.reason == ReservedMemoryError::MissingRanges));
+    assert_eq!(reserved.invalid_nodes().count(), 7);
+}
+
+#[test]
+fn reserved_memory_cells_mismatch() {
+    let source =
+        RESERVED_MALFORMED.replacen("#size-cells = <2>;\n\t\tranges;", "#size-cells = <1>;\n\t\tranges;", 1);
+    let dtb = fdt(&source);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    assert_eq!(fdt.linux_reserved_memory().unwrap().check(), Err(ReservedMemoryError::CellsMismatch));
+}
@@ -0,0 +1,405 @@
+mod common;
+
+use common::{DTB_DATA, fdt};
+use fdtree_rs::firmware::{ScmiProtocolKind, ScmiTransport};
+use fdtree_rs::psci::PsciMethod;
+use fdtree_rs::thermal::TripType;
+use fdtree_rs::LinuxFdt;
+
+static THERMAL_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	tsens: thermal-sensor@1000 {
+		reg = <0x1000 0x100>;
+		#thermal-sensor-cells = <1>;
+	};
+
+	fan: fan {
+		#cooling-cells = <2>;
+	};
+
+	cpu_cooling: cpu-cooling {
+		#cooling-cells = <2>;
+	};
+
+	thermal-zones {
+		cpu-thermal {
+			polling-delay = <1000>;
+			polling-delay-passive = <250>;
+			sustainable-power = <2500>;
+			thermal-sensors = <&tsens 0>;
+
+			trips {
+				cpu_alert: cpu-alert {
+					temperature = <75000>;
+					hysteresis = <2000>;
+					type = "passive";
+				};
+
+				cpu_fan: cpu-fan {
+					temperature = <65000>;
+					hysteresis = <5000>;
+					type = "active";
+				};
+
+				cpu-crit {
+					temperature = <95000>;
+					hysteresis = <0>;
+					type = "critical";
+				};
+			};
+
+			cooling-maps {
+				map0 {
+					trip = <&cpu_alert>;
+					cooling-device = <&cpu_cooling 0xffffffff 3>;
+					contribution = <1024>;
+				};
+
+				map1 {
+					trip = <&cpu_fan>;
+					cooling-device = <&fan 0 1>, <&fan 2 0xffffffff>;
+				};
+			};
+		};
+
+		cold-thermal {
+			polling-delay = <0>;
+			polling-delay-passive = <0>;
+			thermal-sensors = <&tsens 1>;
+
+			trips {
+				cold {
+					temperature = <0xffffec78>;
+					hysteresis = <1000>;
+					type = "hot";
+				};
+			};
+		};
+
+		off-thermal {
+			status = "disabled";
+		};
+	};
+};
+"#;
+
+#[test]
+fn thermal_zones() {
+    let dtb = fdt(THERMAL_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let zones: Vec<_> = fdt.thermal_zones().collect();
+    assert_eq!(zones.iter().map(|z| z.name()).collect::<Vec<_>>(), ["cpu-thermal", "cold-thermal"]);
+
+    let cpu = zones[0];
+    assert_eq!(cpu.polling_delay(), Some(1000));
+    assert_eq!(cpu.polling_delay_passive(), Some(250));
+    assert_eq!(cpu.sustainable_power(), Some(2500));
+    let sensor = cpu.sensors().next().unwrap();
+    assert_eq!((sensor.node.name, sensor.args()), ("thermal-sensor@1000", &[0][..]));
+
+    let trips: Vec<_> = cpu.trips().map(|t| (t.temperature(), t.hysteresis(), t.kind())).collect();
+    assert_eq!(
+        trips,
+        [
+            (Some(75_000), Some(2000), Some(TripType::Passive)),
+            (Some(65_000), Some(5000), Some(TripType::Active)),
+            (Some(95_000), Some(0), Some(TripType::Critical)),
+        ]
+    );
+
+    let cold = zones[1].trips().next().unwrap();
+    assert_eq!(cold.temperature(), Some(-5000));
+    assert_eq!(cold.kind(), Some(TripType::Hot));
+    assert_eq!(zones[1].cooling_maps().count(), 0);
+    assert_eq!(zones[1].sensors().next().unwrap().args(), [1]);
+}
+
+#[test]
+fn thermal_cooling_maps() {
+    let dtb = fdt(THERMAL_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let cpu = fdt.thermal_zones().next().unwrap();
+    let maps: Vec<_> = cpu.cooling_maps().collect();
+    assert_eq!(maps.len(), 2);
+
+    assert_eq!(maps[0].trip().unwrap().node.name, "cpu-alert");
+    assert_eq!(maps[0].contribution(), Some(1024));
+    let device = maps[0].devices().next().unwrap();
+    assert_eq!((device.node.name, device.min_state, device.max_state), ("cpu-cooling", None, Some(3)));
+
+    assert_eq!(maps[1].trip().unwrap().kind(), Some(TripType::Active));
+    assert_eq!(maps[1].contribution(), None);
+    let devices: Vec<_> = maps[1].devices().map(|d| (d.node.name, d.min_state, d.max_state)).collect();
+    assert_eq!(devices, [("fan", Some(0), Some(1)), ("fan", Some(2), None)]);
+
+    assert_eq!(LinuxFdt::new(include_bytes!("../dtb/test.dtb")).unwrap().thermal_zones().count(), 0);
+}
+
+static FIRMWARE_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+	interrupt-parent = <&gic>;
+
+	gic: interrupt-controller@8000000 {
+		reg = <0x0 0x8000000 0x0 0x10000>;
+		#interrupt-cells = <3>;
+		interrupt-controller;
+	};
+
+	mhu: mailbox@2b1f0000 {
+		reg = <0x0 0x2b1f0000 0x0 0x1000>;
+		#mbox-cells = <1>;
+	};
+
+	sram@2e000000 {
+		#address-cells = <1>;
+		#size-cells = <1>;
+		ranges = <0x0 0x0 0x2e000000 0x8000>;
+
+		cpu_scp_lpri: scp-sram@0 {
+			compatible = "arm,scmi-shmem";
+			reg = <0x0 0x80>;
+		};
+
+		cpu_scp_hpri: scp-sram@200 {
+			compatible = "arm,scmi-shmem";
+			reg = <0x200 0x80>;
+		};
+	};
+
+	firmware {
+		optee {
+			compatible = "linaro,optee-tz";
+			method = "smc";
+			interrupts = <1 15 4>;
+		};
+
+		scmi {
+			compatible = "arm,scmi";
+			mboxes = <&mhu 0>, <&mhu 1>;
+			mbox-names = "tx", "rx";
+			shmem = <&cpu_scp_lpri>;
+			#address-cells = <1>;
+			#size-cells = <0>;
+
+			scmi_perf: protocol@13 {
+				reg = <0x13>;
+				#clock-cells = <1>;
+				mboxes = <&mhu 2>;
+				shmem = <&cpu_scp_hpri>;
+			};
+
+			scmi_clk: protocol@14 {
+				reg = <0x14>;
+				#clock-cells = <1>;
+			};
+
+			protocol@80 {
+				reg = <0x80>;
+			};
+		};
+
+		sdei {
+			compatible = "arm,sdei-1.0";
+			method = "hvc";
+		};
+	};
+};
+"#;
+
+static FIRMWARE_SMC_BOARD: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <1>;
+	#size-cells = <1>;
+
+	firmware {
+		scmi {
+			compatible = "arm,scmi-smc";
+			arm,smc-id = <0x82000010>;
+			#address-cells = <1>;
+			#size-cells = <0>;
+
+			protocol@16 {
+				reg = <0x16>;
+				#reset-cells = <1>;
+			};
+		};
+	};
+
+	scpi {
+		compatible = "arm,scpi-pre-1.0";
+		mboxes = <&mhu 1>;
+
+		scpi-clocks {
+			compatible = "arm,scpi-clocks";
+		};
+
+		scpi-sensors {
+			compatible = "arm,scpi-sensors";
+			#thermal-sensor-cells = <1>;
+		};
+	};
+
+	mhu: mailbox {
+		#mbox-cells = <1>;
+	};
+};
+"#;
+
+#[test]
+fn firmware_optee_scmi() {
+    let dtb = fdt(FIRMWARE_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let firmware = fdt.firmware().unwrap();
+
+    let optee = firmware.optee().unwrap();
+    assert_eq!(optee.method(), Some(PsciMethod::Smc));
+    assert_eq!(optee.interrupt().collect::<Vec<_>>(), [1, 15, 4]);
+    assert_eq!(firmware.sdei().unwrap().method(), Some(PsciMethod::Hvc));
+    assert!(fdt.scpi().is_none());
+
+    let scmi = firmware.scmi().unwrap();
+    assert_eq!(scmi.transport(), Some(ScmiTransport::Mailbox));
+    let mboxes: Vec<_> = scmi.mailboxes().map(|(name, mbox)| (name, mbox.node.name, mbox.args().to_vec())).collect();
+    assert_eq!(mboxes, [(Some("tx"), "mailbox@2b1f0000", vec![0]), (Some("rx"), "mailbox@2b1f0000", vec![1])]);
+    assert_eq!(scmi.shmem().collect::<Vec<_>>(), [(0x2e00_0000, 0x80)]);
+
+    let protocols: Vec<_> = scmi.protocols().map(|p| (p.id(), p.kind())).collect();
+    assert_eq!(
+        protocols,
+        [(0x13, Some(ScmiProtocolKind::Performance)), (0x14, Some(ScmiProtocolKind::Clock)), (0x80, None)]
+    );
+    let perf = scmi.protocol(0x13).unwrap();
+    assert_eq!(perf.mailboxes().next().unwrap().args(), [2]);
+    assert_eq!(perf.shmem().collect::<Vec<_>>(), [(0x2e00_0200, 0x80)]);
+    assert_eq!(scmi.protocol(0x14).unwrap().shmem().count(), 0);
+}
+
+#[test]
+fn firmware_scmi_smc_scpi() {
+    let dtb = fdt(FIRMWARE_SMC_BOARD);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let firmware = fdt.firmware().unwrap();
+    assert!(firmware.optee().is_none());
+
+    let scmi = firmware.scmi().unwrap();
+    assert_eq!(scmi.transport(), Some(ScmiTransport::Smc { smc_id: Some(0x8200_0010) }));
+    assert_eq!(scmi.mailboxes().count(), 0);
+    assert_eq!(scmi.protocols().next().unwrap().kind(), Some(ScmiProtocolKind::Reset));
+
+    let scpi = fdt.scpi().unwrap();
+    assert_eq!(scpi.node().parent().unwrap().name, "");
+    assert!(scpi.pre_1_0());
+    assert_eq!(scpi.mailboxes().next().unwrap().node.name, "mailbox");
+    assert_eq!(scpi.clocks().unwrap().name, "scpi-clocks");
+    assert_eq!(scpi.sensors().unwrap().name, "scpi-sensors");
+    assert!(scpi.power_domains().is_none());
+
+    assert!(LinuxFdt::new(include_bytes!("../dtb/test.dtb")).unwrap().firmware().is_none());
+}
+
+static XEN_GUEST: &str = r#"
+/dts-v1/;
+
+/ {
+	#address-cells = <2>;
+	#size-cells = <2>;
+	interrupt-parent = <&gic>;
+
+	gic: interrupt-controller@3001000 {
+		reg = <0x0 0x3001000 0x0 0x1000>;
+		#interrupt-cells = <3>;
+		interrupt-controller;
+	};
+
+	hypervisor {
+		compatible = "xen,xen-4.17", "xen,xen";
+		reg = <0x0 0x38000000 0x0 0x1000000>, <0x1 0x0 0x0 0x40000000>;
+		interrupts = <1 15 0xf08>;
+
+		uefi {
+			xen,uefi-system-table = /bits/ 64 <0x7fe00000>;
+			xen,uefi-mmap-start = /bits/ 64 <0x7fd00000>;
+			xen,uefi-mmap-size = <0x1800>;
+			xen,uefi-mmap-desc-size = <0x30>;
+			xen,uefi-mmap-desc-ver = <1>;
+		};
+	};
+
+	virtio@2000000 {
+		compatible = "virtio,mmio";
+		reg = <0x0 0x2000000 0x0 0x200>;
+		interrupts = <0 40 1>;
+		dma-coherent;
+	};
+
+	virtio@2000200 {
+		compatible = "virtio,mmio";
+		reg = <0x0 0x2000200 0x0 0x200>;
+		interrupts-extended = <&gic 0 41 1>;
+	};
+
+	virtio@2000400 {
+		compatible = "virtio,mmio";
+		reg = <0x0 0x2000400 0x0 0x200>;
+		status = "disabled";
+	};
+};
+"#;
+
+#[test]
+fn virtio_mmio_devices() {
+    let dtb = fdt(XEN_GUEST);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let devices: Vec<_> = fdt.virtio_mmio_devices().collect();
+    assert_eq!(devices.len(), 2);
+
+    assert_eq!(devices[0].registers(), Some((0x200_0000, 0x200)));
+    assert!(devices[0].dma_coherent());
+    let irq = devices[0].interrupts().next().unwrap();
+    assert_eq!((irq.node.name, irq.args()), ("interrupt-controller@3001000", &[0, 40, 1][..]));
+
+    assert_eq!(devices[1].registers(), Some((0x200_0200, 0x200)));
+    assert!(!devices[1].dma_coherent());
+    assert_eq!(devices[1].interrupts().map(|irq| irq.args()[1]).collect::<Vec<_>>(), [41]);
+
+    let fdt = LinuxFdt::new(DTB_DATA).unwrap();
+    let devices: Vec<_> = fdt.virtio_mmio_devices().collect();
+    assert_eq!(devices.len(), 8);
+    for (i, device) in devices.iter().rev().enumerate() {
+        assert_eq!(device.registers(), Some((0x1000_1000 + 0x1000 * i as u64, 0x1000)));
+        let irq = device.interrupts().next().unwrap();
+        assert_eq!(irq.node.name, "plic@c000000");
+        assert_eq!(irq.args(), [i as u32 + 1]);
+    }
+}
+
+#[test]
+fn xen_hypervisor() {
+    let dtb = fdt(XEN_GUEST);
+    let fdt = LinuxFdt::new(&dtb).unwrap();
+    let xen = fdt.hypervisor().unwrap().xen().unwrap();
+
+    assert_eq!(xen.version(), Some("4.17"));
+    assert_eq!(xen.grant_table(), Some((0x3800_0000, 0x100_0000)));
+    assert_eq!(xen.extended_regions().collect::<Vec<_>>(), [(0x1_0000_0000, 0x4000_0000)]);
+    let irq = xen.event_channel_irq().unwrap();
+    assert_eq!(irq.args(), [1, 15, 0xf08]);
+
+    let uefi = xen.uefi().unwrap();
+    assert_eq!(uefi.system_table, 0x7fe0_0000);
+    assert_eq!(uefi.mmap_start, 0x7fd0_0000);
+    assert_eq!((uefi.mmap_size, uefi.mmap_desc_size, uefi.mmap_desc_version), (0x1800, 0x30, 1));
+
+    assert!(LinuxFdt::new(DTB_DATA).unwrap().hypervisor().is_none());
+}